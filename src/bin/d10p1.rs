@@ -1,155 +1,52 @@
+use aoc_2023::d10::{Maze, RenderOptions, TileClass};
+use aoc_2023::grid::ImageExport;
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos {
-    x: i64,
-    y: i64,
-}
-
-impl Pos {
-    fn new(x: i64, y: i64) -> Self {
-        Pos { x, y }
-    }
-
-    fn up(&self) -> Self {
-        Self {
-            y: self.y - 1,
-            ..*self
-        }
-    }
-
-    fn down(&self) -> Self {
-        Self {
-            y: self.y + 1,
-            ..*self
-        }
-    }
-
-    fn left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            ..*self
-        }
-    }
-
-    fn right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            ..*self
-        }
-    }
-
-    fn neighbors(&self) -> [Self; 4] {
-        [self.up(), self.down(), self.left(), self.right()]
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Tile {
-    absolute_pos: Pos,
-    connections: [Pos; 2],
-    raw: char,
+#[cfg(test)]
+fn solve(puzzle: &str) -> i64 {
+    let maze = Maze::parse(puzzle).expect("invalid maze");
+    let (_, distance) = maze.farthest().expect("invalid loop");
+    distance as i64
 }
 
-impl Tile {
-    fn from(raw: char, x: i64, y: i64) -> Self {
-        let pos = Pos::new(x, y);
-
-        let connections = match raw {
-            '|' => [pos.down(), pos.up()],
-            '-' => [pos.left(), pos.right()],
-            'L' => [pos.up(), pos.right()],
-            'J' => [pos.up(), pos.left()],
-            '7' => [pos.down(), pos.left()],
-            'F' => [pos.down(), pos.right()],
-            _ => [pos, pos],
+#[aocd(2023, 10)]
+fn main() {
+    let input = input!();
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let maze = Maze::parse(&input).expect("invalid maze");
+    run.parsed();
+
+    if std::env::args().any(|arg| arg == "--render") {
+        let color = std::io::stdout().is_terminal();
+        let options = RenderOptions {
+            color,
+            distances: color,
+            ..Default::default()
         };
 
-        Self {
-            raw,
-            connections,
-            absolute_pos: pos,
-        }
+        print!("{}", maze.render(&options).expect("invalid loop"));
+        return;
     }
-}
-
-fn parse(puzzle: &str) -> HashMap<Pos, Tile> {
-    puzzle
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .map(move |(x, c)| Tile::from(c, x as i64, y as i64))
-        })
-        .map(|t| (t.absolute_pos, t))
-        .collect()
-}
-
-fn solve(puzzle: &str) -> i64 {
-    let world = parse(puzzle);
-
-    let mut visited: HashSet<Pos> = HashSet::new();
-
-    let starting_tile = world
-        .values()
-        .find(|t| t.raw == 'S')
-        .expect("no starting tile found");
-
-    visited.insert(starting_tile.absolute_pos);
 
-    let mut current = starting_tile
-        .absolute_pos
-        .neighbors()
-        .iter()
-        .filter_map(|p| world.get(p))
-        .find(|t| {
-            t.connections
-                .iter()
-                .any(|p| *p == starting_tile.absolute_pos)
-        })
-        .expect("there is no place to go from starting point");
-
-    loop {
-        visited.insert(current.absolute_pos);
-        current = current
-            .connections
-            .iter()
-            .filter_map(|p| world.get(p))
-            .find(|t| !visited.contains(&t.absolute_pos))
-            .unwrap_or(starting_tile);
-
-        if current.raw == 'S' {
-            break;
-        }
+    if let Some(export) = ImageExport::from_args(std::env::args()) {
+        let grid = maze.to_grid().expect("invalid loop");
+        export
+            .save(&grid, &TileClass::palette())
+            .expect("can not save image");
+        return;
     }
 
-    visited.len() as i64 / 2
-}
-
-#[aocd(2023, 10)]
-fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let (pos, distance) = maze.farthest().expect("invalid loop");
+    run.finish(distance, fingerprint(&input));
+    eprintln!("farthest tile at ({}, {})", pos.x, pos.y);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
 
-    #[test]
-    fn test_solve_against_example() {
-        let puzzle: &str = "
-..F7.
-.FJ|.
-SJ.L7
-|F--J
-LJ...
-";
-
-        assert_eq!(solve(puzzle), 8);
-    }
+    fixture_tests!(10, 1, solve);
 }
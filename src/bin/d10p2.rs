@@ -0,0 +1,59 @@
+use aoc_2023::d10::{enclosed_by_area, Maze, RenderOptions, TileClass};
+use aoc_2023::grid::ImageExport;
+use aoc_2023::report::{fingerprint, Run};
+use aocd::prelude::*;
+use std::io::IsTerminal;
+
+#[cfg(test)]
+fn solve(puzzle: &str) -> i64 {
+    enclosed(&Maze::parse(puzzle).expect("invalid maze"))
+}
+
+fn enclosed(maze: &Maze) -> i64 {
+    let path = maze.main_loop().expect("invalid loop");
+
+    let by_area = enclosed_by_area(&path);
+    let by_scanline = maze.enclosed_by_scanline(&path);
+    assert_eq!(by_area, by_scanline, "enclosed tile methods disagree");
+
+    by_area
+}
+
+#[aocd(2023, 10)]
+fn main() {
+    let input = input!();
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let maze = Maze::parse(&input).expect("invalid maze");
+    run.parsed();
+
+    if std::env::args().any(|arg| arg == "--render") {
+        let color = std::io::stdout().is_terminal();
+        let options = RenderOptions {
+            color,
+            enclosed: true,
+            ..Default::default()
+        };
+
+        print!("{}", maze.render(&options).expect("invalid loop"));
+        return;
+    }
+
+    if let Some(export) = ImageExport::from_args(std::env::args()) {
+        let grid = maze.to_grid().expect("invalid loop");
+        export
+            .save(&grid, &TileClass::palette())
+            .expect("can not save image");
+        return;
+    }
+
+    let result = enclosed(&maze);
+    run.finish(result, fingerprint(&input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::fixture_tests;
+
+    fixture_tests!(10, 2, solve);
+}
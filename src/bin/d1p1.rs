@@ -1,18 +1,46 @@
+use aoc_2023::d1::{self, MissingDigits, Scanner, Summary};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
+use std::io::BufRead;
+
+fn solve(puzzle: impl BufRead, policy: MissingDigits) -> Summary {
+    let scanner = Scanner::digits();
+
+    d1::calibrate(puzzle, policy, |l| scanner.scan(l).map(|c| c.value()))
+        .unwrap_or_else(|e| panic!("can not calibrate: {}", e))
+}
 
 #[aocd(2023, 1)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let result: u32 = input!()
-        .lines()
-        .map(|l| {
-            let mut iter = l.chars().filter_map(|c| c.to_digit(10));
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let policy = MissingDigits::from_args(std::env::args()).expect("bad missing digits policy");
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let summary = solve(&mut puzzle, policy);
+
+    if summary.missing > 0 {
+        eprintln!("{}", summary);
+    }
+
+    run.finish(summary.sum, puzzle.hash());
+}
 
-            let first = iter.next().unwrap();
-            let last = iter.last().unwrap_or(first);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::fixture_tests;
 
-            first * 10 + last
-        })
-        .sum();
+    fixture_tests!(1, 1, |puzzle: &str| {
+        solve(puzzle.as_bytes(), MissingDigits::Error).sum
+    });
 
-    println!("{}", result);
+    #[test]
+    #[should_panic(expected = "no digits in line 2")]
+    fn test_blank_line_is_an_error_by_default() {
+        solve("1abc2\n\ntreb7uchet\n".as_bytes(), MissingDigits::default());
+    }
 }
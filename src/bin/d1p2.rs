@@ -1,32 +1,51 @@
-// Simplier than my original solution (now d1p2a1) and preserves everything
-// from part 1, but may be less performant.
+// Scans every line once for all the words. The first take, now d1p2a2,
+// rewrote every line once per word (`one` -> `one1one`) and d1p2a1 tries
+// every word at every offset. Both check they agree with this one on
+// generated inputs.
 
+use aoc_2023::d1::{self, MissingDigits, Scanner, Summary, Vocabulary};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
+use std::io::BufRead;
+
+fn solve(puzzle: impl BufRead, vocabulary: &Vocabulary, policy: MissingDigits) -> Summary {
+    let scanner = Scanner::new(vocabulary);
+
+    d1::calibrate(puzzle, policy, |l| scanner.scan(l).map(|c| c.value()))
+        .unwrap_or_else(|e| panic!("can not calibrate: {}", e))
+}
 
 #[aocd(2023, 1)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let result: u32 = input!()
-        .lines()
-        .map(|l| {
-            l.replace("one", "one1one")
-                .replace("two", "two2two")
-                .replace("three", "three3three")
-                .replace("four", "four4four")
-                .replace("five", "five5five")
-                .replace("six", "six6six")
-                .replace("seven", "seven7seven")
-                .replace("eight", "eight8eight")
-                .replace("nine", "nine9nine")
-        })
-        .map(|l| {
-            let mut iter = l.chars().filter_map(|c| c.to_digit(10));
-
-            let first = iter.next().unwrap();
-            let last = iter.last().unwrap_or(first);
-
-            first * 10 + last
-        })
-        .sum();
-
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let vocabulary = Vocabulary::from_args(std::env::args()).expect("bad vocabulary");
+    let policy = MissingDigits::from_args(std::env::args()).expect("bad missing digits policy");
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let summary = solve(&mut puzzle, &vocabulary, policy);
+
+    if summary.missing > 0 {
+        eprintln!("{}", summary);
+    }
+
+    run.finish(summary.sum, puzzle.hash());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::fixture_tests;
+
+    fixture_tests!(1, 2, |puzzle: &str| {
+        solve(
+            puzzle.as_bytes(),
+            &Vocabulary::english(),
+            MissingDigits::Error,
+        )
+        .sum
+    });
 }
@@ -1,3 +1,6 @@
+use aoc_2023::d1::{self, MissingDigits};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
 
 fn try_parse_digit(slice: &[char]) -> Option<u32> {
@@ -19,29 +22,69 @@ fn try_parse_digit(slice: &[char]) -> Option<u32> {
     }
 }
 
+fn calibration_value(line: &str) -> Option<u32> {
+    let chars: Vec<_> = line.chars().collect();
+    let mut first: Option<u32> = None;
+    let mut last: Option<u32> = None;
+
+    for i in 0..chars.len() {
+        let slice = &chars[i..];
+        if let Some(digit) = try_parse_digit(slice) {
+            if first.is_none() {
+                first = Some(digit);
+            }
+
+            last = Some(digit);
+        }
+    }
+
+    Some(first? * 10 + last?)
+}
+
 #[aocd(2023, 1)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let result: u32 = input!()
-        .lines()
-        .map(|l| {
-            let chars: Vec<_> = l.chars().collect();
-            let mut first: Option<u32> = None;
-            let mut last: Option<u32> = None;
-
-            for i in 0..chars.len() {
-                let slice = &chars[i..];
-                if let Some(digit) = try_parse_digit(slice) {
-                    if first.is_none() {
-                        first = Some(digit);
-                    }
-
-                    last = Some(digit);
-                }
-            }
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let policy = MissingDigits::from_args(std::env::args()).expect("bad missing digits policy");
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let summary = d1::calibrate(&mut puzzle, policy, calibration_value)
+        .unwrap_or_else(|e| panic!("can not calibrate: {}", e));
 
-            first.unwrap() * 10 + last.unwrap()
-        })
-        .sum();
+    if summary.missing > 0 {
+        eprintln!("{}", summary);
+    }
+
+    run.finish(summary.sum, puzzle.hash());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::d1::Scanner;
+    use aoc_2023::differential::Differential;
+    use aoc_2023::fixture_tests;
+
+    fixture_tests!(1, 2, |puzzle: &str| sum(puzzle, calibration_value));
 
-    println!("{}", result);
+    fn sum(puzzle: &str, value: impl FnMut(&str) -> Option<u32>) -> u64 {
+        d1::calibrate(puzzle.as_bytes(), MissingDigits::Error, value)
+            .unwrap_or_else(|e| panic!("can not calibrate: {:?}", e))
+            .sum
+    }
+
+    #[test]
+    fn test_agrees_with_d1p2() {
+        let scanner = Scanner::spelled();
+
+        Differential::new(1, 2)
+            .variant("d1p2a1", |puzzle: &str| sum(puzzle, calibration_value))
+            .variant("d1p2", |puzzle: &str| {
+                sum(puzzle, |line| scanner.scan(line).map(|c| c.value()))
+            })
+            .sizes(&[50])
+            .check();
+    }
 }
@@ -0,0 +1,77 @@
+// The first take at part 2: simpler than d1p2a1 and preserves everything
+// from part 1, but rewrites every line once per word so it may be less
+// performant. Words keep their letters around the digit (`one` -> `one1one`)
+// so overlapping words like `twone` still find both digits.
+
+use aoc_2023::d1::{self, MissingDigits};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
+use aocd::prelude::*;
+
+fn calibration_value(line: &str) -> Option<u32> {
+    let line = line
+        .replace("one", "one1one")
+        .replace("two", "two2two")
+        .replace("three", "three3three")
+        .replace("four", "four4four")
+        .replace("five", "five5five")
+        .replace("six", "six6six")
+        .replace("seven", "seven7seven")
+        .replace("eight", "eight8eight")
+        .replace("nine", "nine9nine");
+
+    let mut iter = line.chars().filter_map(|c| c.to_digit(10));
+
+    let first = iter.next()?;
+    let last = iter.next_back().unwrap_or(first);
+
+    Some(first * 10 + last)
+}
+
+#[aocd(2023, 1)]
+fn fetch_input() -> String {
+    input!()
+}
+
+fn main() {
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let policy = MissingDigits::from_args(std::env::args()).expect("bad missing digits policy");
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let summary = d1::calibrate(&mut puzzle, policy, calibration_value)
+        .unwrap_or_else(|e| panic!("can not calibrate: {}", e));
+
+    if summary.missing > 0 {
+        eprintln!("{}", summary);
+    }
+
+    run.finish(summary.sum, puzzle.hash());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_2023::d1::Scanner;
+    use aoc_2023::differential::Differential;
+    use aoc_2023::fixture_tests;
+
+    fixture_tests!(1, 2, |puzzle: &str| sum(puzzle, calibration_value));
+
+    fn sum(puzzle: &str, value: impl FnMut(&str) -> Option<u32>) -> u64 {
+        d1::calibrate(puzzle.as_bytes(), MissingDigits::Error, value)
+            .unwrap_or_else(|e| panic!("can not calibrate: {:?}", e))
+            .sum
+    }
+
+    #[test]
+    fn test_agrees_with_d1p2() {
+        let scanner = Scanner::spelled();
+
+        Differential::new(1, 2)
+            .variant("d1p2a2", |puzzle: &str| sum(puzzle, calibration_value))
+            .variant("d1p2", |puzzle: &str| {
+                sum(puzzle, |line| scanner.scan(line).map(|c| c.value()))
+            })
+            .sizes(&[50])
+            .check();
+    }
+}
@@ -1,117 +1,135 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
+use std::io::BufRead;
 
 const RED_LIMIT: u32 = 12;
 const GREEN_LIMIT: u32 = 13;
 const BLUE_LIMIT: u32 = 14;
 
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .trim()
-        .to_lowercase()
-        .replace(|c| c == ':' || c == ';', ",")
-        .replace(", ", ",")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
 }
 
-fn tokenize(puzzle: &str) -> impl Iterator<Item = impl Iterator<Item = (&str, &str)>> {
-    puzzle.lines().map(|game| {
-        game.split(',')
-            .map(|s| s.split_once(' '))
-            .map(|o| o.expect("no space character while tokenizing"))
-    })
+#[derive(Debug, PartialEq, Eq)]
+struct Game {
+    id: u32,
+    /// Every `(count, color)` shown, the subsets they were in don't matter.
+    cubes: Vec<(u32, Color)>,
 }
 
-fn solve(puzzle: &str) -> u32 {
-    let puzzle = normalize(puzzle);
-    let puzzle = tokenize(&puzzle);
+impl Game {
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        let (id, subsets) = parse::labelled(line, "Game")?;
 
-    puzzle.fold(0, |acc, mut game| {
-        let first_token = game
-            .next()
-            .expect("tried to read game id but no token to read");
+        let cubes = subsets
+            .split(';')
+            .flat_map(|subset| subset.split(','))
+            .map(|cube| {
+                let (count, color) = cube.trim().split_once(" ")?;
 
-        let id: u32 = match first_token {
-            ("game", id) => id.parse().expect("game id is not a number"),
-            _ => panic!("expected first token to be a game token"),
-        };
+                let color = match color.as_str() {
+                    "red" => Color::Red,
+                    "green" => Color::Green,
+                    "blue" => Color::Blue,
+                    other => return Err(color.error(format!("unknown color {:?}", other))),
+                };
+
+                Ok((count.parse()?, color))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { id, cubes })
+    }
+
+    fn is_possible(&self) -> bool {
+        self.cubes.iter().all(|(count, color)| {
+            let limit = match color {
+                Color::Red => RED_LIMIT,
+                Color::Green => GREEN_LIMIT,
+                Color::Blue => BLUE_LIMIT,
+            };
+
+            *count <= limit
+        })
+    }
+}
+
+fn solve(puzzle: impl BufRead) -> u64 {
+    let mut sum = 0;
+    let mut number = 0;
+
+    reader::for_each_line(puzzle, |line| {
+        number += 1;
+        if line.trim().is_empty() {
+            return;
+        }
 
-        let impossible = game.any(|(count, color)| {
-            let count: u32 = count.parse().expect("subset count is not a number");
-
-            match color {
-                "red" => count > RED_LIMIT,
-                "green" => count > GREEN_LIMIT,
-                "blue" => count > BLUE_LIMIT,
-                _ => panic!("unexpected color"),
-            }
-        });
-
-        if impossible {
-            acc
-        } else {
-            acc + id
+        let game = Game::parse(Span::at_line(line, number)).unwrap_or_else(|e| panic!("{}", e));
+        if game.is_possible() {
+            sum += game.id as u64;
         }
     })
+    .expect("can not read puzzle");
+
+    sum
 }
 
 #[aocd(2023, 2)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let result = solve(&mut puzzle);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
+
+    fixture_tests!(2, 1, |puzzle: &str| solve(puzzle.as_bytes()));
 
     const PUZZLE: &str = "Game 1: 10 green, 5 blue; 1 red, 9 green, 10 blue
 Game 2: 7 green, 5 red, 3 blue
 ";
 
-    const EXAMPLE_PUZZLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-
-    const PROBLEMATIC_PUZZLE: &str = "Game 1: 12 red; 1 red";
-
-    #[test]
-    fn test_normalize() {
-        let expected = "game 1,10 green,5 blue,1 red,9 green,10 blue\ngame 2,7 green,5 red,3 blue";
-        assert_eq!(normalize(PUZZLE), expected);
-    }
-
     #[test]
-    fn test_tokenize() {
-        let normalized = normalize(PUZZLE);
-        let result = tokenize(&normalized)
-            .map(|game| game.collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        let expected = vec![
-            vec![
-                ("game", "1"),
-                ("10", "green"),
-                ("5", "blue"),
-                ("1", "red"),
-                ("9", "green"),
-                ("10", "blue"),
+    fn test_parse() {
+        let line = Span::new(PUZZLE.lines().next().unwrap());
+        let expected = Game {
+            id: 1,
+            cubes: vec![
+                (10, Color::Green),
+                (5, Color::Blue),
+                (1, Color::Red),
+                (9, Color::Green),
+                (10, Color::Blue),
             ],
-            vec![("game", "2"), ("7", "green"), ("5", "red"), ("3", "blue")],
-        ];
+        };
 
-        assert_eq!(result, expected);
+        assert_eq!(Game::parse(line), Ok(expected));
     }
 
     #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 8);
+    fn test_parse_error_location() {
+        let error = Game::parse(Span::at_line("Game 3: 1 red, 2 purple", 3)).unwrap_err();
+
+        assert_eq!((error.line, error.column), (3, 18));
+        assert_eq!(error.message, "unknown color \"purple\"");
     }
 
     #[test]
-    fn test_solve_against_problematic() {
-        assert_eq!(solve(PROBLEMATIC_PUZZLE), 1);
+    fn test_generated() {
+        generate::check_answers(2, 1, &[100], |puzzle: &str| solve(puzzle.as_bytes()));
     }
 }
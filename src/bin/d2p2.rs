@@ -1,113 +1,137 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
+use std::io::BufRead;
 
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .trim()
-        .to_lowercase()
-        .replace(|c| c == ':' || c == ';', ",")
-        .replace(", ", ",")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
 }
 
-fn tokenize(puzzle: &str) -> impl Iterator<Item = impl Iterator<Item = (&str, &str)>> {
-    puzzle.lines().map(|game| {
-        game.split(',')
-            .map(|s| s.split_once(' '))
-            .map(|o| o.expect("no space character while tokenizing"))
-    })
+#[derive(Debug, PartialEq, Eq)]
+struct Game {
+    id: u32,
+    /// Every `(count, color)` shown, the subsets they were in don't matter.
+    cubes: Vec<(u32, Color)>,
 }
 
-fn solve(puzzle: &str) -> u32 {
-    let puzzle = normalize(puzzle);
-    let puzzle = tokenize(&puzzle);
+impl Game {
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        let (id, subsets) = parse::labelled(line, "Game")?;
+
+        let cubes = subsets
+            .split(';')
+            .flat_map(|subset| subset.split(','))
+            .map(|cube| {
+                let (count, color) = cube.trim().split_once(" ")?;
+
+                let color = match color.as_str() {
+                    "red" => Color::Red,
+                    "green" => Color::Green,
+                    "blue" => Color::Blue,
+                    other => return Err(color.error(format!("unknown color {:?}", other))),
+                };
 
-    puzzle.fold(0, |acc, mut game| {
-        game.next().expect("can not read game tokens");
+                Ok((count.parse()?, color))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { id, cubes })
+    }
 
+    /// The product of the fewest cubes of each color that make the game
+    /// possible.
+    fn power(&self) -> u32 {
         let mut min_red = 0;
         let mut min_green = 0;
         let mut min_blue = 0;
 
-        for (count, color) in game {
-            let count: u32 = count.parse().expect("count is not a number");
-
-            match color {
-                "red" => {
-                    if count > min_red {
-                        min_red = count;
-                    }
-                }
-                "green" => {
-                    if count > min_green {
-                        min_green = count;
-                    }
-                }
-                "blue" => {
-                    if count > min_blue {
-                        min_blue = count;
-                    }
-                }
-                _ => panic!("unexpected color"),
-            }
+        for (count, color) in self.cubes.iter().copied() {
+            let min = match color {
+                Color::Red => &mut min_red,
+                Color::Green => &mut min_green,
+                Color::Blue => &mut min_blue,
+            };
+
+            *min = count.max(*min);
         }
 
-        let power = min_red * min_green * min_blue;
+        min_red * min_green * min_blue
+    }
+}
+
+fn solve(puzzle: impl BufRead) -> u64 {
+    let mut sum = 0;
+    let mut number = 0;
+
+    reader::for_each_line(puzzle, |line| {
+        number += 1;
+        if line.trim().is_empty() {
+            return;
+        }
 
-        power + acc
+        let game = Game::parse(Span::at_line(line, number)).unwrap_or_else(|e| panic!("{}", e));
+        sum += game.power() as u64;
     })
+    .expect("can not read puzzle");
+
+    sum
 }
 
 #[aocd(2023, 2)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let result = solve(&mut puzzle);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
+
+    fixture_tests!(2, 2, |puzzle: &str| solve(puzzle.as_bytes()));
 
     const PUZZLE: &str = "Game 1: 10 green, 5 blue; 1 red, 9 green, 10 blue
 Game 2: 7 green, 5 red, 3 blue
-";
-
-    const EXAMPLE_PUZZLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 ";
 
     #[test]
-    fn test_normalize() {
-        let expected = "game 1,10 green,5 blue,1 red,9 green,10 blue\ngame 2,7 green,5 red,3 blue";
-        assert_eq!(normalize(PUZZLE), expected);
+    fn test_parse() {
+        let line = Span::new(PUZZLE.lines().next().unwrap());
+        let expected = Game {
+            id: 1,
+            cubes: vec![
+                (10, Color::Green),
+                (5, Color::Blue),
+                (1, Color::Red),
+                (9, Color::Green),
+                (10, Color::Blue),
+            ],
+        };
+
+        assert_eq!(Game::parse(line), Ok(expected));
     }
 
     #[test]
-    fn test_tokenize() {
-        let normalized = normalize(PUZZLE);
-        let result = tokenize(&normalized)
-            .map(|game| game.collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        let expected = vec![
-            vec![
-                ("game", "1"),
-                ("10", "green"),
-                ("5", "blue"),
-                ("1", "red"),
-                ("9", "green"),
-                ("10", "blue"),
-            ],
-            vec![("game", "2"), ("7", "green"), ("5", "red"), ("3", "blue")],
-        ];
+    fn test_parse_error_location() {
+        let error = Game::parse(Span::at_line("Game 3: 1 red, 2 purple", 3)).unwrap_err();
 
-        assert_eq!(result, expected);
+        assert_eq!((error.line, error.column), (3, 18));
+        assert_eq!(error.message, "unknown color \"purple\"");
     }
 
     #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 2286);
+    fn test_generated() {
+        generate::check_answers(2, 2, &[100], |puzzle: &str| solve(puzzle.as_bytes()));
     }
 }
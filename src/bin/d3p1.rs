@@ -1,6 +1,9 @@
 // The implementation I used to solve part 2 could solve this with little
 // modifications probably.
 
+use aoc_2023::d3::{self, SchematicClass};
+use aoc_2023::grid::ImageExport;
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 use std::collections::HashMap;
 
@@ -19,7 +22,7 @@ impl Point {
 #[derive(Copy, Clone, Debug)]
 enum PointValue {
     Digit(char),
-    Symbol(char),
+    Symbol(#[allow(dead_code)] char),
 }
 
 impl PointValue {
@@ -185,28 +188,29 @@ fn solve(puzzle: &str) -> i32 {
 #[aocd(2023, 3)]
 fn main() {
     let input = input!();
+
+    if let Some(export) = ImageExport::from_args(std::env::args()) {
+        export
+            .save(&d3::to_grid(&input), &SchematicClass::palette())
+            .expect("can not save image");
+        return;
+    }
+
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
     let result = solve(&input);
-    println!("{}", result);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
 
-    const EXAMPLE_PUZZLE: &str = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..    
-";
+    fixture_tests!(3, 1, solve);
 
     #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 4361);
+    fn test_generated() {
+        generate::check_answers(3, 1, &[60], solve);
     }
 }
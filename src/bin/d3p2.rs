@@ -1,3 +1,6 @@
+use aoc_2023::d3::{self, SchematicClass};
+use aoc_2023::grid::ImageExport;
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 use std::collections::HashMap;
 
@@ -15,10 +18,15 @@ impl Point {
     }
 }
 
+/// A horizontal run of digits. The id is unique per number in the schematic,
+/// so two numbers with the same value and row are never confused.
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Component {
-    Number(i32),
-    Symbol(char),
+struct NumberSpan {
+    id: usize,
+    y: i32,
+    start_x: i32,
+    end_x: i32,
+    value: i32,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
@@ -33,81 +41,45 @@ impl Gear {
 }
 
 struct Engine {
-    components: HashMap<Point, Component>,
-    component_map: HashMap<Point, Point>,
+    /// Indexed by id.
+    numbers: Vec<NumberSpan>,
+    /// The id of the number every digit belongs to.
+    digits: HashMap<Point, usize>,
+    symbols: HashMap<Point, char>,
 }
 
 impl Engine {
     fn new() -> Self {
         Self {
-            components: HashMap::new(),
-            component_map: HashMap::new(),
+            numbers: Vec::new(),
+            digits: HashMap::new(),
+            symbols: HashMap::new(),
         }
     }
 
-    fn resolve_position(&self, x: i32, y: i32) -> Point {
-        let position = Point::new(x, y);
-        *self.component_map.get(&position).unwrap_or(&position)
-    }
-
-    fn symbols(&self) -> HashMap<Point, char> {
-        let mut symbols = HashMap::new();
-
-        for (position, component) in &self.components {
-            if let Component::Symbol(symbol) = component {
-                symbols.insert(*position, *symbol);
-            }
-        }
-
-        symbols
-    }
-
-    fn get_adjacent_components(&self, x: i32, y: i32) -> HashMap<Point, Component> {
-        let mut deduper = HashMap::new();
-
-        let deltas = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            // (0, 0),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
-
-        for (dx, dy) in deltas {
-            let position = self.resolve_position(x + dx, y + dy);
-            let component = self.components.get(&position).copied();
-
-            if let Some(component) = component {
-                deduper.insert(position, component);
-            }
-        }
-
-        deduper
+    /// Numbers with a digit in one of the 8 cells around `position`. A
+    /// number touching it with several digits is only returned once.
+    fn get_adjacent_numbers(&self, position: Point) -> Vec<&NumberSpan> {
+        let mut ids: Vec<usize> = (position.y - 1..=position.y + 1)
+            .flat_map(|y| (position.x - 1..=position.x + 1).map(move |x| Point::new(x, y)))
+            .filter_map(|neighbour| self.digits.get(&neighbour).copied())
+            .collect();
+
+        ids.sort();
+        ids.dedup();
+        ids.iter().map(|id| &self.numbers[*id]).collect()
     }
 
     fn get_gears(&self) -> HashMap<Point, Gear> {
         let mut gears = HashMap::new();
-        let symbols = self.symbols();
 
-        for (position, symbol) in symbols {
-            if symbol == '*' {
-                let adjacents = self.get_adjacent_components(position.x, position.y);
-
-                let numbers: Vec<_> = adjacents
-                    .values()
-                    .filter_map(|c| match c {
-                        Component::Number(number) => Some(number),
-                        _ => None,
-                    })
-                    .collect();
+        for (position, symbol) in &self.symbols {
+            if *symbol == '*' {
+                let numbers = self.get_adjacent_numbers(*position);
 
                 if numbers.len() == 2 {
-                    let ratio = numbers[0] * numbers[1];
-                    gears.insert(position, Gear::new(ratio));
+                    let ratio = numbers[0].value * numbers[1].value;
+                    gears.insert(*position, Gear::new(ratio));
                 }
             }
         }
@@ -132,16 +104,10 @@ impl EngineBuilder {
     }
 
     fn push_digit(&mut self, x: i32, y: i32, digit: char) {
-        let position = Point::new(x, y);
-
         if self.digit_buffer.is_empty() {
-            self.number_begin = position;
+            self.number_begin = Point::new(x, y);
         }
 
-        self.engine
-            .component_map
-            .insert(position, self.number_begin);
-
         self.digit_buffer.push(digit);
     }
 
@@ -150,26 +116,29 @@ impl EngineBuilder {
             return;
         }
 
-        let number: i32 = self
+        let value: i32 = self
             .digit_buffer
             .parse()
             .expect("invalid number when consuming digit buffer");
 
-        self.engine
-            .components
-            .insert(self.number_begin, Component::Number(number));
+        let span = NumberSpan {
+            id: self.engine.numbers.len(),
+            y: self.number_begin.y,
+            start_x: self.number_begin.x,
+            end_x: self.number_begin.x + self.digit_buffer.len() as i32 - 1,
+            value,
+        };
 
+        for x in span.start_x..=span.end_x {
+            self.engine.digits.insert(Point::new(x, span.y), span.id);
+        }
+
+        self.engine.numbers.push(span);
         self.digit_buffer.clear();
     }
 
     fn push_symbol(&mut self, x: i32, y: i32, symbol: char) {
-        let position = Point::new(x, y);
-
-        self.engine
-            .components
-            .insert(position, Component::Symbol(symbol));
-        self.engine.component_map.insert(position, position);
-
+        self.engine.symbols.insert(Point::new(x, y), symbol);
         self.consume_digit_buffer();
     }
 
@@ -208,28 +177,85 @@ fn solve(puzzle: &str) -> i32 {
 #[aocd(2023, 3)]
 fn main() {
     let input = input!();
+
+    if let Some(export) = ImageExport::from_args(std::env::args()) {
+        export
+            .save(&d3::to_grid(&input), &SchematicClass::palette())
+            .expect("can not save image");
+        return;
+    }
+
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
     let result = solve(&input);
-    println!("{}", result);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
 
-    const EXAMPLE_PUZZLE: &str = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-";
+    fixture_tests!(3, 2, solve);
+
+    #[test]
+    fn test_equal_adjacent_numbers() {
+        assert_eq!(solve("12*12\n"), 144);
+        assert_eq!(solve("12.\n.*.\n12.\n"), 144);
+    }
+
+    #[test]
+    fn test_numbers_touching_grid_edge() {
+        assert_eq!(solve("5.\n*.\n3.\n"), 15);
+        assert_eq!(solve(".42\n..*\n.42\n"), 1764);
+        assert_eq!(solve("7\n*\n"), 0);
+    }
+
+    #[test]
+    fn test_dash_is_a_symbol_not_a_sign() {
+        assert_eq!(solve("-7*3\n"), 21);
+        assert_eq!(solve("7*-3\n"), 0);
+    }
+
+    #[test]
+    fn test_number_ids_are_unique() {
+        let mut builder = EngineBuilder::new();
+
+        for (x, c) in "11.11".chars().enumerate() {
+            builder.push(x as i32, 0, c);
+        }
+        builder.push_void();
+
+        let engine = builder.build();
+        let ids: Vec<_> = engine.numbers.iter().map(|n| n.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(engine.numbers[1].start_x, 3);
+        assert_eq!(engine.numbers[1].end_x, 4);
+    }
+
+    #[test]
+    fn test_numbers_around_a_gear_are_counted_once() {
+        let mut builder = EngineBuilder::new();
+
+        for (y, line) in ["111", ".*.", "222"].iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                builder.push(x as i32, y as i32, c);
+            }
+            builder.push_void();
+        }
+
+        let engine = builder.build();
+        let adjacent: Vec<_> = engine
+            .get_adjacent_numbers(Point::new(1, 1))
+            .iter()
+            .map(|n| n.id)
+            .collect();
+        assert_eq!(adjacent, vec![0, 1]);
+        assert_eq!(engine.get_gears()[&Point::new(1, 1)].ratio, 111 * 222);
+    }
 
     #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 467835);
+    fn test_generated() {
+        generate::check_answers(3, 2, &[60], solve);
     }
 }
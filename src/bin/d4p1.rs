@@ -1,16 +1,8 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
-
-fn normalize(puzzle: &str) -> String {
-    return puzzle
-        .trim()
-        .split(' ')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join(" ")
-        .replace("Card ", "")
-        .replace(": ", "|")
-        .replace(" | ", "|");
-}
+use std::io::BufRead;
 
 #[derive(Debug)]
 struct Card {
@@ -21,28 +13,15 @@ struct Card {
 }
 
 impl Card {
-    fn parse(s: &str) -> Self {
-        let mut iter = s.split('|');
-
-        let id: i32 = iter.next().unwrap().parse().unwrap();
-        let winning_numbers: Vec<i32> = iter
-            .next()
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let own_numbers: Vec<i32> = iter
-            .next()
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        Self {
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        let (id, numbers) = parse::labelled(line, "Card")?;
+        let (winning_numbers, own_numbers) = numbers.split_once("|")?;
+
+        Ok(Self {
             id,
-            winning_numbers,
-            own_numbers,
-        }
+            winning_numbers: parse::integers(winning_numbers)?,
+            own_numbers: parse::integers(own_numbers)?,
+        })
     }
 
     fn own_winning_numbers(&self) -> impl Iterator<Item = i32> + '_ {
@@ -63,43 +42,66 @@ impl Card {
     }
 }
 
-fn solve(puzzle: &str) -> i32 {
-    let puzzle = normalize(puzzle);
-    puzzle.lines().map(Card::parse).map(|c| c.value()).sum()
+fn solve(puzzle: impl BufRead) -> i64 {
+    let mut sum = 0;
+
+    let mut number = 0;
+
+    reader::for_each_line(puzzle, |line| {
+        number += 1;
+        if line.trim().is_empty() {
+            return;
+        }
+
+        let card = Card::parse(Span::at_line(line, number)).unwrap_or_else(|e| panic!("{}", e));
+        sum += card.value() as i64;
+    })
+    .expect("can not read puzzle");
+
+    sum
 }
 
 #[aocd(2023, 4)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let result = solve(&mut puzzle);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
 
-    #[test]
-    fn test_normalize() {
-        let puzzle = "Card   9: 92 39 44 48 96 72 43 78 98 86 | 76 87  9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16  6 78 48  8
-Card  10: 66 44 15 56 88 27 54 51  5 92 | 44 92 18 56 22 85 40 76 90 83  5 13 35 59 27 65 62 15 95 94 81 39 88 54  6";
-
-        let expected = "9|92 39 44 48 96 72 43 78 98 86|76 87 9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16 6 78 48 8
-10|66 44 15 56 88 27 54 51 5 92|44 92 18 56 22 85 40 76 90 83 5 13 35 59 27 65 62 15 95 94 81 39 88 54 6";
+    fixture_tests!(4, 1, |puzzle: &str| solve(puzzle.as_bytes()));
 
-        assert_eq!(normalize(puzzle), expected);
+    #[test]
+    fn test_parse() {
+        let puzzle = Span::new("Card   9: 92 39 44 48 96 72 43 78 98 86 | 76 87  9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16  6 78 48  8
+Card  10: 66 44 15 56 88 27 54 51  5 92 | 44 92 18 56 22 85 40 76 90 83  5 13 35 59 27 65 62 15 95 94 81 39 88 54  6");
+
+        let cards: Vec<_> = puzzle.lines().map(|l| Card::parse(l).unwrap()).collect();
+
+        assert_eq!(cards[0].id, 9);
+        assert_eq!(cards[1].id, 10);
+        assert_eq!(
+            cards[1].winning_numbers,
+            [66, 44, 15, 56, 88, 27, 54, 51, 5, 92]
+        );
+        assert_eq!(cards[1].own_numbers.len(), 25);
+
+        let error = Card::parse(Span::at_line("Card 2: 13 32 | 61 3O", 2)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 20));
     }
 
     #[test]
-    fn test_solve_against_example() {
-        let puzzle: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-";
-
-        assert_eq!(solve(puzzle), 13);
+    fn test_generated() {
+        generate::check_answers(4, 1, &[200], |puzzle: &str| solve(puzzle.as_bytes()));
     }
 }
@@ -1,50 +1,28 @@
-// This solution is not efficient. It uses more than a GB and it is very slow
-// if you don't run it in release mode.
-
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
-
-fn normalize(puzzle: &str) -> String {
-    return puzzle
-        .trim()
-        .split(' ')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join(" ")
-        .replace("Card ", "")
-        .replace(": ", "|")
-        .replace(" | ", "|");
-}
+use std::collections::VecDeque;
+use std::io::BufRead;
 
 #[derive(Debug, Clone)]
 struct Card {
+    #[allow(dead_code)]
     id: i32,
     winning_numbers: Vec<i32>,
     own_numbers: Vec<i32>,
 }
 
 impl Card {
-    fn parse(s: &str) -> Self {
-        let mut iter = s.split('|');
-
-        let id: i32 = iter.next().unwrap().parse().unwrap();
-        let winning_numbers: Vec<i32> = iter
-            .next()
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let own_numbers: Vec<i32> = iter
-            .next()
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        let (id, numbers) = parse::labelled(line, "Card")?;
+        let (winning_numbers, own_numbers) = numbers.split_once("|")?;
 
-        Self {
+        Ok(Self {
             id,
-            winning_numbers,
-            own_numbers,
-        }
+            winning_numbers: parse::integers(winning_numbers)?,
+            own_numbers: parse::integers(own_numbers)?,
+        })
     }
 
     fn own_winning_numbers(&self) -> impl Iterator<Item = i32> + '_ {
@@ -55,103 +33,142 @@ impl Card {
     }
 }
 
-struct Deck {
-    original_cards: Vec<Card>,
-    cards: Vec<Card>,
+/// Copies won for the cards still to come. Only as many cards as the most
+/// matches seen so far are tracked, however long the pile is.
+#[derive(Debug, Default)]
+struct Copies {
+    pending: VecDeque<u64>,
 }
 
-impl Deck {
-    fn parse(puzzle: &str) -> Self {
-        let cards: Vec<_> = puzzle.lines().map(Card::parse).collect();
-        Self {
-            original_cards: cards.clone(),
-            cards,
-        }
+impl Copies {
+    /// How many instances of the next card there are, the original included.
+    fn take_next(&mut self) -> u64 {
+        1 + self.pending.pop_front().unwrap_or(0)
     }
 
-    fn nextn(&self, id: i32, n: i32) -> impl Iterator<Item = &Card> + '_ {
-        self.original_cards
-            .iter()
-            .skip_while(move |c| c.id != id)
-            .skip(1)
-            .take(n as usize)
-    }
+    /// Each of the next `n` cards gets `count` more copies.
+    fn win(&mut self, n: usize, count: u64) {
+        if self.pending.len() < n {
+            self.pending.resize(n, 0);
+        }
 
-    fn put_card(&mut self, card: Card) {
-        self.cards.push(card);
+        for copies in self.pending.iter_mut().take(n) {
+            *copies += count;
+        }
     }
+}
 
-    fn len(&self) -> i32 {
-        self.cards.len() as i32
-    }
+fn solve(puzzle: impl BufRead) -> u64 {
+    let mut copies = Copies::default();
+    let mut total = 0;
 
-    fn at(&self, index: i32) -> &Card {
-        &self.cards[index as usize]
-    }
-}
+    let mut number = 0;
 
-fn solve(puzzle: &str) -> i32 {
-    let puzzle = normalize(puzzle);
-    let mut deck = Deck::parse(&puzzle);
-    let mut index = 0;
-
-    while index < deck.len() {
-        let card = deck.at(index);
-        let matches = card.own_winning_numbers().count();
-        let nextn = deck
-            .nextn(card.id, matches as i32)
-            .cloned()
-            .collect::<Vec<_>>();
-        for c in nextn {
-            deck.put_card(c);
+    reader::for_each_line(puzzle, |line| {
+        number += 1;
+        if line.trim().is_empty() {
+            return;
         }
-        index += 1;
-    }
 
-    deck.len()
+        let card = Card::parse(Span::at_line(line, number)).unwrap_or_else(|e| panic!("{}", e));
+        let count = copies.take_next();
+        copies.win(card.own_winning_numbers().count(), count);
+        total += count;
+    })
+    .expect("can not read puzzle");
+
+    total
 }
 
 #[aocd(2023, 4)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let result = solve(&mut puzzle);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::differential::Differential;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
 
-    const EXAMPLE_PUZZLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11    
-";
+    fixture_tests!(4, 2, |puzzle: &str| solve(puzzle.as_bytes()));
 
     #[test]
-    fn test_normalize() {
-        let puzzle = "Card   9: 92 39 44 48 96 72 43 78 98 86 | 76 87  9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16  6 78 48  8
-Card  10: 66 44 15 56 88 27 54 51  5 92 | 44 92 18 56 22 85 40 76 90 83  5 13 35 59 27 65 62 15 95 94 81 39 88 54  6";
-
-        let expected = "9|92 39 44 48 96 72 43 78 98 86|76 87 9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16 6 78 48 8
-10|66 44 15 56 88 27 54 51 5 92|44 92 18 56 22 85 40 76 90 83 5 13 35 59 27 65 62 15 95 94 81 39 88 54 6";
+    fn test_parse() {
+        let puzzle = Span::new("Card   9: 92 39 44 48 96 72 43 78 98 86 | 76 87  9 45 98 47 31 44 34 92 43 54 72 39 50 96 77 86 62 13 16  6 78 48  8
+Card  10: 66 44 15 56 88 27 54 51  5 92 | 44 92 18 56 22 85 40 76 90 83  5 13 35 59 27 65 62 15 95 94 81 39 88 54  6");
+
+        let cards: Vec<_> = puzzle.lines().map(|l| Card::parse(l).unwrap()).collect();
+
+        assert_eq!(cards[0].id, 9);
+        assert_eq!(cards[1].id, 10);
+        assert_eq!(
+            cards[1].winning_numbers,
+            [66, 44, 15, 56, 88, 27, 54, 51, 5, 92]
+        );
+        assert_eq!(cards[1].own_numbers.len(), 25);
+
+        let error = Card::parse(Span::at_line("Card 2: 13 32 | 61 3O", 2)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 20));
+    }
 
-        assert_eq!(normalize(puzzle), expected);
+    #[test]
+    fn test_copies() {
+        let mut copies = Copies::default();
+
+        assert_eq!(copies.take_next(), 1);
+        copies.win(2, 1);
+        assert_eq!(copies.take_next(), 2);
+        copies.win(3, 2);
+        assert_eq!(copies.take_next(), 4);
+        assert_eq!(copies.take_next(), 3);
+        assert_eq!(copies.take_next(), 3);
+        assert!(copies.pending.is_empty());
+        assert_eq!(copies.take_next(), 1);
     }
 
     #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 30);
+    fn test_generated() {
+        generate::check_answers(4, 2, &[200], |puzzle: &str| solve(puzzle.as_bytes()));
+    }
+
+    /// The straightforward way, cloning every card won and going through the
+    /// whole pile.
+    fn solve_by_cloning(puzzle: &str) -> usize {
+        let cards: Vec<Card> = puzzle
+            .lines()
+            .enumerate()
+            .map(|(i, l)| Card::parse(Span::at_line(l, i + 1)).unwrap())
+            .collect();
+        let mut pile: Vec<(usize, Card)> = cards.iter().cloned().enumerate().collect();
+        let mut instances = 0;
+
+        while let Some((position, card)) = pile.pop() {
+            instances += 1;
+
+            let won = card.own_winning_numbers().count();
+            for (next, copy) in cards.iter().enumerate().skip(position + 1).take(won) {
+                pile.push((next, copy.clone()));
+            }
+        }
+
+        instances
     }
 
     #[test]
-    fn test_nextn() {
-        let deck = Deck::parse(&normalize(EXAMPLE_PUZZLE));
-        let nextn = deck.nextn(1, 2).collect::<Vec<_>>();
-        assert_eq!(nextn.len(), 2);
-        assert_eq!(nextn[0].id, 2);
-        assert_eq!(nextn[1].id, 3);
+    fn test_variants_agree() {
+        Differential::new(4, 2)
+            .variant("counting", |puzzle: &str| solve(puzzle.as_bytes()))
+            .variant("cloning", solve_by_cloning)
+            .sizes(&[1, 12])
+            .check();
     }
 }
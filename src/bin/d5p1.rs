@@ -1,18 +1,7 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .trim()
-        .replacen("seeds: ", "", 1)
-        .lines()
-        .filter(|l| match l.chars().next() {
-            Some(c) => c.is_ascii_digit(),
-            None => true,
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
 struct RangeMapper {
     source_start: i64,
     source_end: i64,
@@ -30,28 +19,14 @@ impl RangeMapper {
         }
     }
 
-    fn parse(s: &str) -> Self {
-        let mut iter = s.split(' ');
-
-        let target_start = iter
-            .next()
-            .expect("no target start to read")
-            .parse()
-            .expect("target start is not a number");
-
-        let source_start = iter
-            .next()
-            .expect("no source start to read")
-            .parse()
-            .expect("source start is not a number");
-
-        let range_length = iter
-            .next()
-            .expect("no range length to read")
-            .parse()
-            .expect("range length is not a number");
-
-        Self::new(source_start, target_start, range_length)
+    /// Parses `target_start source_start range_length`.
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        match parse::integers(line)?[..] {
+            [target_start, source_start, range_length] => {
+                Ok(Self::new(source_start, target_start, range_length))
+            }
+            _ => Err(line.error("expected 3 numbers")),
+        }
     }
 
     fn source_range(&self) -> std::ops::Range<i64> {
@@ -83,10 +58,19 @@ struct CompositeRangeMapper {
 }
 
 impl CompositeRangeMapper {
-    fn parse(s: &str) -> Self {
-        Self {
-            mappers: s.lines().map(RangeMapper::parse).collect(),
+    /// Parses a `x-to-y map:` section.
+    fn parse(section: Span) -> Result<Self, ParseError> {
+        let mut lines = section.lines();
+        let header = lines.next().ok_or_else(|| section.error("empty map"))?;
+        let (name, _) = parse::key_value(header)?;
+
+        if !name.as_str().ends_with(" map") {
+            return Err(name.error("expected a map name"));
         }
+
+        Ok(Self {
+            mappers: lines.map(RangeMapper::parse).collect::<Result<_, _>>()?,
+        })
     }
 
     fn map(&self, source: i64) -> Option<i64> {
@@ -94,19 +78,34 @@ impl CompositeRangeMapper {
     }
 }
 
-fn solve(puzzle: &str) -> i64 {
-    let puzzle = normalize(puzzle);
-    let mut iter = puzzle.split("\n\n");
+/// The seed numbers and the maps, in order.
+fn parse(puzzle: &str) -> Result<(Vec<i64>, Vec<CompositeRangeMapper>), ParseError> {
+    let puzzle = Span::new(puzzle);
+    let sections = parse::sections(puzzle);
+    let (seeds, maps) = sections
+        .split_first()
+        .ok_or_else(|| puzzle.error("no seed list input"))?;
+
+    let (key, seeds) = parse::key_value(*seeds)?;
+    if key.as_str() != "seeds" {
+        return Err(key.error("expected the seed list"));
+    }
 
-    let seeds: Vec<i64> = iter
-        .next()
-        .expect("no seed list input")
-        .split(' ')
-        .map(|n| n.parse().expect("seed number is not a number"))
-        .collect();
+    let mappers = maps
+        .iter()
+        .map(|m| CompositeRangeMapper::parse(*m))
+        .collect::<Result<_, _>>()?;
+
+    Ok((parse::integers(seeds)?, mappers))
+}
 
-    let mappers: Vec<_> = iter.map(CompositeRangeMapper::parse).collect();
+#[cfg(test)]
+fn solve(puzzle: &str) -> i64 {
+    let (seeds, mappers) = parse(puzzle).unwrap_or_else(|e| panic!("{}", e));
+    lowest_location(&seeds, &mappers)
+}
 
+fn lowest_location(seeds: &[i64], mappers: &[CompositeRangeMapper]) -> i64 {
     seeds
         .iter()
         .map(|s| mappers.iter().fold(*s, |acc, m| m.map(acc).unwrap_or(acc)))
@@ -117,41 +116,24 @@ fn solve(puzzle: &str) -> i64 {
 #[aocd(2023, 5)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let (seeds, mappers) = parse(&input).unwrap_or_else(|e| panic!("{}", e));
+    run.parsed();
+
+    let result = lowest_location(&seeds, &mappers);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
 
-    #[test]
-    fn test_normalize() {
-        let puzzle: &str = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-";
-
-        let expected: &str = "79 14 55 13
-
-50 98 2
-52 50 48
-
-0 15 37
-37 52 2
-39 0 15";
-        assert_eq!(normalize(puzzle), expected);
-    }
+    fixture_tests!(5, 1, solve);
 
     #[test]
-    fn test_solve_against_example() {
+    fn test_parse() {
         let puzzle: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -162,32 +144,17 @@ soil-to-fertilizer map:
 0 15 37
 37 52 2
 39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
 ";
 
-        assert_eq!(solve(puzzle), 35);
+        let (seeds, mappers) = parse(puzzle).unwrap();
+        assert_eq!(seeds, [79, 14, 55, 13]);
+        assert_eq!(mappers.len(), 2);
+        assert_eq!(mappers[0].mappers.len(), 2);
+        assert_eq!(mappers[1].mappers.len(), 3);
+
+        let error = parse(&puzzle.replace("52 50 48", "52 50")).err().unwrap();
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.message, "expected 3 numbers");
     }
 
     #[test]
@@ -198,4 +165,9 @@ humidity-to-location map:
         assert_eq!(mapper.map(99), Some(51));
         assert_eq!(mapper.map(100), None);
     }
+
+    #[test]
+    fn test_generated() {
+        generate::check_answers(5, 1, &[10], solve);
+    }
 }
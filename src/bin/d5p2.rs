@@ -1,20 +1,9 @@
 // Run this in release mode, go for a coffee and come back after 5 mins :)
 
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .trim()
-        .replacen("seeds: ", "", 1)
-        .lines()
-        .filter(|l| match l.chars().next() {
-            Some(c) => c.is_ascii_digit(),
-            None => true,
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
 struct RangeMapper {
     source_start: i64,
     source_end: i64,
@@ -32,28 +21,14 @@ impl RangeMapper {
         }
     }
 
-    fn parse(s: &str) -> Self {
-        let mut iter = s.split(' ');
-
-        let target_start = iter
-            .next()
-            .expect("no target start to read")
-            .parse()
-            .expect("target start is not a number");
-
-        let source_start = iter
-            .next()
-            .expect("no source start to read")
-            .parse()
-            .expect("source start is not a number");
-
-        let range_length = iter
-            .next()
-            .expect("no range length to read")
-            .parse()
-            .expect("range length is not a number");
-
-        Self::new(source_start, target_start, range_length)
+    /// Parses `target_start source_start range_length`.
+    fn parse(line: Span) -> Result<Self, ParseError> {
+        match parse::integers(line)?[..] {
+            [target_start, source_start, range_length] => {
+                Ok(Self::new(source_start, target_start, range_length))
+            }
+            _ => Err(line.error("expected 3 numbers")),
+        }
     }
 
     fn source_range(&self) -> std::ops::Range<i64> {
@@ -85,10 +60,19 @@ struct CompositeRangeMapper {
 }
 
 impl CompositeRangeMapper {
-    fn parse(s: &str) -> Self {
-        Self {
-            mappers: s.lines().map(RangeMapper::parse).collect(),
+    /// Parses a `x-to-y map:` section.
+    fn parse(section: Span) -> Result<Self, ParseError> {
+        let mut lines = section.lines();
+        let header = lines.next().ok_or_else(|| section.error("empty map"))?;
+        let (name, _) = parse::key_value(header)?;
+
+        if !name.as_str().ends_with(" map") {
+            return Err(name.error("expected a map name"));
         }
+
+        Ok(Self {
+            mappers: lines.map(RangeMapper::parse).collect::<Result<_, _>>()?,
+        })
     }
 
     fn map(&self, source: i64) -> Option<i64> {
@@ -96,18 +80,37 @@ impl CompositeRangeMapper {
     }
 }
 
+/// The seed numbers and the maps, in order.
+fn parse(puzzle: &str) -> Result<(Vec<i64>, Vec<CompositeRangeMapper>), ParseError> {
+    let puzzle = Span::new(puzzle);
+    let sections = parse::sections(puzzle);
+    let (seeds, maps) = sections
+        .split_first()
+        .ok_or_else(|| puzzle.error("no seed list input"))?;
+
+    let (key, seeds) = parse::key_value(*seeds)?;
+    if key.as_str() != "seeds" {
+        return Err(key.error("expected the seed list"));
+    }
+
+    let mappers = maps
+        .iter()
+        .map(|m| CompositeRangeMapper::parse(*m))
+        .collect::<Result<_, _>>()?;
+
+    Ok((parse::integers(seeds)?, mappers))
+}
+
+#[cfg(test)]
 fn solve(puzzle: &str) -> i64 {
-    let puzzle = normalize(puzzle);
-    let mut iter = puzzle.split("\n\n");
+    let (seeds, mappers) = parse(puzzle).unwrap_or_else(|e| panic!("{}", e));
+    lowest_location(&seeds, &mappers)
+}
 
+fn lowest_location(seeds: &[i64], mappers: &[CompositeRangeMapper]) -> i64 {
     type SeedIter = Box<dyn Iterator<Item = i64>>;
 
-    let seed_range: SeedIter = iter
-        .next()
-        .expect("no seed list input")
-        .split(' ')
-        .map(|n| n.parse::<i64>().expect("seed start/length is not a number"))
-        .collect::<Vec<_>>()
+    let seed_range: SeedIter = seeds
         .chunks_exact(2)
         .map(|chunk| {
             let start = chunk[0];
@@ -119,8 +122,6 @@ fn solve(puzzle: &str) -> i64 {
         .reduce(|acc, r| Box::new(acc.chain(r)) as SeedIter)
         .expect("could not chain seed ranges");
 
-    let mappers: Vec<_> = iter.map(CompositeRangeMapper::parse).collect();
-
     seed_range
         .map(|s| mappers.iter().fold(s, |acc, m| m.map(acc).unwrap_or(acc)))
         .min()
@@ -130,41 +131,77 @@ fn solve(puzzle: &str) -> i64 {
 #[aocd(2023, 5)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let (seeds, mappers) = parse(&input).unwrap_or_else(|e| panic!("{}", e));
+    run.parsed();
+
+    let result = lowest_location(&seeds, &mappers);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::differential::Differential;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::generate;
+
+    fixture_tests!(5, 2, solve);
+
+    /// Tries every location from 0 up, following the maps backwards to
+    /// the seeds that end up there, until one of them is in a seed range.
+    /// Only fast for small generated inputs.
+    fn solve_backwards(puzzle: &str) -> i64 {
+        let (seeds, mappers) = parse(puzzle).unwrap_or_else(|e| panic!("{}", e));
+        let ranges: Vec<std::ops::Range<i64>> = seeds
+            .chunks_exact(2)
+            .map(|chunk| chunk[0]..chunk[0] + chunk[1])
+            .collect();
+
+        (0..i64::MAX)
+            .find(|location| {
+                let mut values = vec![*location];
+
+                for composite in mappers.iter().rev() {
+                    // Any range could have mapped to the value, or none,
+                    // but only if it's the one that maps its source.
+                    values = values
+                        .iter()
+                        .flat_map(|value| {
+                            composite
+                                .mappers
+                                .iter()
+                                .filter(|m| m.target_range().contains(value))
+                                .map(|m| m.source_start + value - m.target_start)
+                                .chain([*value])
+                                .filter(|source| {
+                                    composite.map(*source).unwrap_or(*source) == *value
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .collect();
+                    values.sort();
+                    values.dedup();
+                }
+
+                values
+                    .iter()
+                    .any(|seed| ranges.iter().any(|r| r.contains(seed)))
+            })
+            .expect("no location comes from a seed")
+    }
 
     #[test]
-    fn test_normalize() {
-        let puzzle: &str = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-";
-
-        let expected: &str = "79 14 55 13
-
-50 98 2
-52 50 48
-
-0 15 37
-37 52 2
-39 0 15";
-        assert_eq!(normalize(puzzle), expected);
+    fn test_variants_agree() {
+        Differential::new(5, 2)
+            .variant("seeds", solve)
+            .variant("backwards", solve_backwards)
+            .sizes(&[2, 10])
+            .check();
     }
 
     #[test]
-    fn test_solve_against_example() {
+    fn test_parse() {
         let puzzle: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -175,32 +212,17 @@ soil-to-fertilizer map:
 0 15 37
 37 52 2
 39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
 ";
 
-        assert_eq!(solve(puzzle), 46);
+        let (seeds, mappers) = parse(puzzle).unwrap();
+        assert_eq!(seeds, [79, 14, 55, 13]);
+        assert_eq!(mappers.len(), 2);
+        assert_eq!(mappers[0].mappers.len(), 2);
+        assert_eq!(mappers[1].mappers.len(), 3);
+
+        let error = parse(&puzzle.replace("52 50 48", "52 50")).err().unwrap();
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.message, "expected 3 numbers");
     }
 
     #[test]
@@ -211,4 +233,9 @@ humidity-to-location map:
         assert_eq!(mapper.map(99), Some(51));
         assert_eq!(mapper.map(100), None);
     }
+
+    #[test]
+    fn test_generated() {
+        generate::check_answers(5, 2, &[10], solve);
+    }
 }
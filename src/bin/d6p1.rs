@@ -1,3 +1,5 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 
 // did some random math while in the toilet and validated with geogebra
@@ -52,23 +54,51 @@ impl Race {
     }
 }
 
-fn parse(puzzle: &str) -> impl Iterator<Item = Race> + '_ {
-    let mut parsed_lines = puzzle.lines().map(|l| {
-        l.split_once(':')
-            .unwrap()
-            .1
-            .split_whitespace()
-            .map(|n| n.parse::<i64>().expect("could not parse number from input"))
-    });
+/// The numbers of the `Time:` and `Distance:` lines.
+fn parse_lines(puzzle: &str) -> Result<(Span<'_>, Span<'_>), ParseError> {
+    let puzzle = Span::new(puzzle);
+    let mut lines = puzzle.lines().filter(|l| !l.as_str().trim().is_empty());
 
-    let times_iter = parsed_lines.next().expect("can not read times line");
-    let records_iter = parsed_lines.next().expect("can not read records line");
+    let mut line = |name: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| puzzle.error(format!("no {} line", name)))?;
+        let (key, value) = parse::key_value(line)?;
 
-    times_iter.zip(records_iter).map(|(t, r)| Race::new(t, r))
+        if key.as_str() != name {
+            return Err(key.error(format!("expected {:?}", name)));
+        }
+
+        Ok(value)
+    };
+
+    Ok((line("Time")?, line("Distance")?))
 }
 
+fn parse(puzzle: &str) -> Result<Vec<Race>, ParseError> {
+    let (time_line, record_line) = parse_lines(puzzle)?;
+    let times: Vec<i64> = parse::integers(time_line)?;
+    let records: Vec<i64> = parse::integers(record_line)?;
+
+    if times.len() != records.len() {
+        return Err(record_line.error("not as many records as times"));
+    }
+
+    Ok(times
+        .into_iter()
+        .zip(records)
+        .map(|(t, r)| Race::new(t, r))
+        .collect())
+}
+
+#[cfg(test)]
 fn solve(puzzle: &str) -> i64 {
-    parse(puzzle)
+    ways_to_win(&parse(puzzle).unwrap_or_else(|e| panic!("{}", e)))
+}
+
+fn ways_to_win(races: &[Race]) -> i64 {
+    races
+        .iter()
         .map(|r| r.compute_winner_solutions().count() as i64)
         .reduce(|acc, n| acc * n)
         .expect("no solutions count to reduce")
@@ -77,27 +107,30 @@ fn solve(puzzle: &str) -> i64 {
 #[aocd(2023, 6)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let races = parse(&input).unwrap_or_else(|e| panic!("{}", e));
+    run.parsed();
+
+    let result = ways_to_win(&races);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::fixtures;
+    use aoc_2023::generate;
 
-    const EXAMPLE_PUZZLE: &str = "Time:      7  15   30
-Distance:  9  40  200
-";
+    fixture_tests!(6, 1, solve);
 
     #[test]
     fn test_parse() {
         let expected = vec![Race::new(7, 9), Race::new(15, 40), Race::new(30, 200)];
-        assert_eq!(parse(EXAMPLE_PUZZLE).collect::<Vec<_>>(), expected);
-    }
+        assert_eq!(parse(&fixtures::read(6, "example")), Ok(expected));
 
-    #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 288);
+        let error = parse("Time: 7 15\nDistance: 9 4O\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 13));
     }
 
     #[test]
@@ -112,4 +145,9 @@ Distance:  9  40  200
         assert_eq!(Race::new(15, 40).compute_winner_solutions().count(), 8);
         assert_eq!(Race::new(30, 200).compute_winner_solutions().count(), 9);
     }
+
+    #[test]
+    fn test_generated() {
+        generate::check_answers(6, 1, &[6], solve);
+    }
 }
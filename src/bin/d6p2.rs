@@ -1,3 +1,5 @@
+use aoc_2023::parse::{self, ParseError, Span};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 
 // This is just part 1 solution for a single big race instead of many :P
@@ -45,51 +47,79 @@ impl Race {
     }
 }
 
-fn parse(puzzle: &str) -> Race {
-    let mut parsed_lines = puzzle.lines().map(|l| {
-        l.split_once(':')
-            .unwrap()
-            .1
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join("")
-            .parse::<i64>()
-            .expect("could not parse line as a single number")
-    });
+/// The numbers of the `Time:` and `Distance:` lines.
+fn parse_lines(puzzle: &str) -> Result<(Span<'_>, Span<'_>), ParseError> {
+    let puzzle = Span::new(puzzle);
+    let mut lines = puzzle.lines().filter(|l| !l.as_str().trim().is_empty());
+
+    let mut line = |name: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| puzzle.error(format!("no {} line", name)))?;
+        let (key, value) = parse::key_value(line)?;
+
+        if key.as_str() != name {
+            return Err(key.error(format!("expected {:?}", name)));
+        }
+
+        Ok(value)
+    };
 
-    let time_limit = parsed_lines.next().expect("no time limit");
-    let record_distance = parsed_lines.next().expect("no record distance");
+    Ok((line("Time")?, line("Distance")?))
+}
+
+/// Both lines as a single number, ignoring the spaces between digits.
+fn parse(puzzle: &str) -> Result<Race, ParseError> {
+    let (time, record) = parse_lines(puzzle)?;
 
-    Race::new(time_limit, record_distance)
+    let number = |span: Span| {
+        let digits: String = span.split_whitespace().map(|s| s.as_str()).collect();
+        digits
+            .parse::<i64>()
+            .map_err(|e| span.error(format!("can not parse {:?}: {}", digits, e)))
+    };
+
+    Ok(Race::new(number(time)?, number(record)?))
 }
 
+#[cfg(test)]
 fn solve(puzzle: &str) -> i64 {
-    parse(puzzle).compute_winner_solutions().count() as i64
+    ways_to_win(&parse(puzzle).unwrap_or_else(|e| panic!("{}", e)))
+}
+
+fn ways_to_win(race: &Race) -> i64 {
+    race.compute_winner_solutions().count() as i64
 }
 
 #[aocd(2023, 6)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let race = parse(&input).unwrap_or_else(|e| panic!("{}", e));
+    run.parsed();
+
+    let result = ways_to_win(&race);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::fixtures;
+    use aoc_2023::generate;
 
-    const EXAMPLE_PUZZLE: &str = "Time:      7  15   30
-Distance:  9  40  200
-";
+    fixture_tests!(6, 2, solve);
 
     #[test]
     fn test_parse() {
-        assert_eq!(parse(EXAMPLE_PUZZLE), Race::new(71530, 940200));
-    }
+        assert_eq!(
+            parse(&fixtures::read(6, "example")),
+            Ok(Race::new(71530, 940200))
+        );
 
-    #[test]
-    fn test_solve_against_example() {
-        assert_eq!(solve(EXAMPLE_PUZZLE), 71503);
+        let error = parse("Time: 7 15\nRecord: 940200\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
     }
 
     #[test]
@@ -104,4 +134,9 @@ Distance:  9  40  200
         assert_eq!(Race::new(15, 40).compute_winner_solutions().count(), 8);
         assert_eq!(Race::new(30, 200).compute_winner_solutions().count(), 9);
     }
+
+    #[test]
+    fn test_generated() {
+        generate::check_answers(6, 2, &[1, 2, 3], solve);
+    }
 }
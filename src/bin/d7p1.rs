@@ -1,108 +1,59 @@
+use aoc_2023::d7::{self, ExplainFormat, Rules};
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
-use std::collections::HashMap;
+use std::io::BufRead;
 
-// This encoding is better for char comparison
-fn encode_cards(cards: &str) -> impl Iterator<Item = char> + '_ {
-    cards.chars().map(|c| match c {
-        'T' => 'B',
-        'J' => 'C',
-        'Q' => 'D',
-        'K' => 'E',
-        'A' => 'F',
-        _ => c,
-    })
+fn solve(puzzle: impl BufRead, rules: &Rules) -> i64 {
+    d7::solve(puzzle, rules).expect("can not read puzzle")
 }
 
-struct Hand {
-    cards: Vec<char>,
-    bid: i64,
+#[aocd(2023, 7)]
+fn fetch_input() -> String {
+    input!()
 }
 
-impl Hand {
-    fn parse(s: &str) -> Self {
-        let (cards, bid) = s.split_once(' ').expect("could not split hand in two");
-
-        let cards = encode_cards(cards).collect();
-        let bid: i64 = bid.parse().expect("bid is not a number");
-
-        Self { cards, bid }
-    }
-
-    fn kind_power(&self) -> i64 {
-        let mut freq: HashMap<char, i64> = HashMap::new();
-
-        for c in self.cards.iter() {
-            *freq.entry(*c).or_insert(0) += 1;
-        }
-
-        let mut counts: Vec<i64> = freq.values().copied().collect();
-        counts.sort_by(|a, b| b.cmp(a));
-
-        let biggest_count = counts.first().copied().unwrap_or(0);
-        let second_biggest_count = counts.get(1).copied().unwrap_or(0);
-
-        biggest_count * 10 + second_biggest_count
+fn main() {
+    let mut puzzle = reader::open(std::env::args(), fetch_input);
+    let rules = Rules::from_args(std::env::args(), &Rules::standard())
+        .unwrap_or_else(|e| panic!("bad rules: {}", e));
+
+    if let Some(format) = ExplainFormat::from_args(std::env::args()) {
+        // Explaining lists every play, so there's no point in streaming.
+        let mut input = String::new();
+        puzzle
+            .read_to_string(&mut input)
+            .expect("can not read puzzle");
+
+        let explanation = d7::explain(&input, &rules);
+        print!("{}", d7::render_explanation(&explanation, format));
+        return;
     }
-}
-
-fn solve(puzzle: &str) -> i64 {
-    let mut hands: Vec<_> = puzzle.trim().lines().map(Hand::parse).collect();
-    hands.sort_by(|h1, h2| {
-        h1.kind_power()
-            .cmp(&h2.kind_power())
-            .then_with(|| h1.cards.cmp(&h2.cards))
-    });
 
-    hands
-        .iter()
-        .enumerate()
-        .map(|(i, h)| {
-            let rank = (i + 1) as i64;
-            rank * h.bid
-        })
-        .sum()
-}
-
-#[aocd(2023, 7)]
-fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let mut puzzle = Hashing::new(puzzle);
+    let result = solve(&mut puzzle, &rules);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::d7::{Hand, HandKind};
+    use aoc_2023::fixture_tests;
 
-    #[test]
-    fn test_solve_against_example() {
-        let puzzle: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
-
-        assert_eq!(solve(puzzle), 6440);
-    }
-
-    #[test]
-    fn test_hand_kind_power() {
-        let full_house = Hand::parse("23332 0");
-        let three_of_a_kind = Hand::parse("TTT98 0");
-
-        assert!(full_house.kind_power() > three_of_a_kind.kind_power());
-    }
+    fixture_tests!(7, 1, |puzzle: &str| solve(
+        puzzle.as_bytes(),
+        &Rules::standard()
+    ));
 
     #[test]
-    fn test_interpretation_sensitive_case() {
-        let input = "K8KK6 75
-TAK97 148
-8345K 129
-QT45K 170
-";
+    fn test_hand_kind() {
+        let full_house = Hand::parse("23332", &Rules::standard());
+        let three_of_a_kind = Hand::parse("TTT98", &Rules::standard());
 
-        let result = 1235;
-        assert_eq!(solve(input), result);
+        assert_eq!(full_house.kind(), HandKind::FullHouse);
+        assert_eq!(three_of_a_kind.kind(), HandKind::ThreeOfAKind);
+        assert!(full_house > three_of_a_kind);
     }
 }
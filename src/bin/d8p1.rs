@@ -1,99 +1,75 @@
-use std::collections::HashMap;
-
+use aoc_2023::d8::{Network, NodePattern};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
 
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .replace("\n\n", "\n")
-        .replace([' ', '(', ')'], "")
-        .replace('=', ",")
-}
+const MAX_STEPS: u64 = 1_000_000_000;
 
+#[cfg(test)]
 fn solve(puzzle: &str) -> i64 {
-    let puzzle = normalize(puzzle);
-
-    let mut directions = puzzle
-        .lines()
-        .next()
-        .expect("no directions to read")
-        .chars()
-        .cycle();
-
-    let network: HashMap<&str, (&str, &str)> = puzzle
-        .lines()
-        .skip(1)
-        .map(|line| {
-            let (from, to) = line.split_once(',').expect("can not split node");
-            let (left, right) = to.split_once(',').expect("can not split node paths");
-            (from, (left, right))
-        })
-        .collect();
-
-    let mut node_id = "AAA";
-    let mut steps: i64 = 0;
+    steps(&Network::parse(puzzle).expect("invalid network"))
+}
 
-    while node_id != "ZZZ" {
-        let node_paths = network.get(node_id).expect("node id not found");
-        let dir = directions.next().expect("can not read next direction");
+fn steps(network: &Network) -> i64 {
+    let start = NodePattern::parse("AAA");
+    let goal = NodePattern::parse("ZZZ");
 
-        node_id = match dir {
-            'L' => node_paths.0,
-            'R' => node_paths.1,
-            _ => panic!("invalid direction"),
-        };
+    let (_, steps) = network
+        .walk(|id| start.matches(id), |id| goal.matches(id), MAX_STEPS)
+        .pop()
+        .expect("no AAA node");
 
-        steps += 1;
-    }
+    steps.expect("can not walk to ZZZ") as i64
+}
 
-    steps
+/// Output of `--dot` or `--reachability`, if any of them was passed.
+fn debug_report(network: &Network) -> Option<String> {
+    let is_start = |id: &str| id == "AAA";
+    let is_end = |id: &str| id == "ZZZ";
+
+    std::env::args().find_map(|arg| match arg.as_str() {
+        "--dot" => {
+            let start = network.id("AAA").expect("no AAA node");
+            let path = network.trace(start, is_end);
+            Some(network.to_dot(is_start, is_end, &[path]))
+        }
+        "--reachability" => Some(network.reachability(is_start, is_end).to_string()),
+        _ => None,
+    })
 }
 
 #[aocd(2023, 8)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let network = Network::parse(&input).expect("invalid network");
+    run.parsed();
+
+    if let Some(report) = debug_report(&network) {
+        print!("{}", report);
+        return;
+    }
+
+    let result = steps(&network);
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
 
-    const SHORT_EXAMPLE: &str = "LLR
-
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)
-";
-
-    const LONG_EXAMPLE: &str = "RL
-
-AAA = (BBB, CCC)
-BBB = (DDD, EEE)
-CCC = (ZZZ, GGG)
-DDD = (DDD, DDD)
-EEE = (EEE, EEE)
-GGG = (GGG, GGG)
-ZZZ = (ZZZ, ZZZ)
-";
-
-    #[test]
-    fn test_normalize() {
-        let expected: &str = "LLR
-AAA,BBB,BBB
-BBB,AAA,ZZZ
-ZZZ,ZZZ,ZZZ
-";
-        assert_eq!(normalize(SHORT_EXAMPLE), expected);
-    }
+    fixture_tests!(8, 1, solve);
 
     #[test]
-    fn test_solve_against_long_example() {
-        assert_eq!(solve(LONG_EXAMPLE), 2);
-    }
+    #[should_panic(expected = "can not walk to ZZZ")]
+    fn test_solve_unreachable_goal() {
+        solve(
+            "L
 
-    #[test]
-    fn test_solve_against_short_example() {
-        assert_eq!(solve(SHORT_EXAMPLE), 6);
+AAA = (BBB, BBB)
+BBB = (AAA, AAA)
+ZZZ = (ZZZ, ZZZ)
+",
+        );
     }
 }
@@ -1,117 +1,165 @@
 // The idea of using LCM came from Reddit memes :P
 // My original brute force solution that looks like part 1 didn't finish after
 // a long time so I had to discard it.
+//
+// LCM only works because the inputs have clean cycles, so this now finds the
+// actual cycle of every ghost and combines them with CRT instead.
 
+use aoc_2023::d8::{Analysis, Cycle, Network, NodePattern};
+use aoc_2023::report::{fingerprint, Run};
 use aocd::prelude::*;
-use std::collections::HashMap;
 
-fn gcd(a: u128, b: u128) -> u128 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-fn lcm(a: u128, b: u128) -> u128 {
-    (a / gcd(a, b)) * b
-}
-
-fn normalize(puzzle: &str) -> String {
-    puzzle
-        .replace("\n\n", "\n")
-        .replace([' ', '(', ')'], "")
-        .replace('=', ",")
+#[cfg(test)]
+fn analyze(puzzle: &str) -> Analysis {
+    analyze_network(&Network::parse(puzzle).expect("invalid network"))
 }
 
-fn follow(network: &HashMap<&str, (&str, &str)>, directions: &str, node_id: &str) -> u128 {
-    let mut directions = directions.chars().cycle();
-
-    let mut node_id = node_id;
-    let mut steps: u128 = 0;
+fn analyze_network(network: &Network) -> Analysis {
+    let start = NodePattern::parse("*A");
+    let end = NodePattern::parse("*Z");
 
-    while !node_id.ends_with('Z') {
-        let node_paths = network.get(node_id).expect("node id not found");
-        let dir = directions.next().expect("can not read next direction");
-
-        node_id = match dir {
-            'L' => node_paths.0,
-            'R' => node_paths.1,
-            _ => panic!("invalid direction"),
-        };
-
-        steps += 1;
-    }
+    let cycles = network
+        .find_all(&start)
+        .map(|node| Cycle::find(network, node, |id| end.matches(id)))
+        .collect();
 
-    steps
+    Analysis::new(cycles)
 }
 
-fn solve(puzzle: &str) -> u128 {
-    let puzzle = normalize(puzzle);
-
-    let directions = puzzle.lines().next().expect("no directions to read");
-
-    let network: HashMap<&str, (&str, &str)> = puzzle
-        .lines()
-        .skip(1)
-        .map(|line| {
-            let (from, to) = line.split_once(',').expect("can not split node");
-            let (left, right) = to.split_once(',').expect("can not split node paths");
-            (from, (left, right))
-        })
-        .collect();
-
-    network
-        .keys()
-        .filter(|id| id.ends_with('A'))
-        .copied()
-        .map(|id| follow(&network, directions, id))
-        .reduce(lcm)
-        .expect("nothing to reduce")
+/// Output of `--dot` or `--reachability`, if any of them was passed. The DOT
+/// graph shows the path of every ghost.
+fn debug_report(network: &Network) -> Option<String> {
+    let is_start = |id: &str| id.ends_with('A');
+    let is_end = |id: &str| id.ends_with('Z');
+
+    std::env::args().find_map(|arg| match arg.as_str() {
+        "--dot" => {
+            let paths: Vec<Vec<u32>> = network
+                .nodes()
+                .filter(|node| is_start(network.name(*node)))
+                .map(|node| network.trace(node, is_end))
+                .collect();
+            Some(network.to_dot(is_start, is_end, &paths))
+        }
+        "--reachability" => Some(network.reachability(is_start, is_end).to_string()),
+        _ => None,
+    })
 }
 
 #[aocd(2023, 8)]
 fn main() {
     let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let network = Network::parse(&input).expect("invalid network");
+    run.parsed();
+
+    if let Some(report) = debug_report(&network) {
+        print!("{}", report);
+        return;
+    }
+
+    let analysis = analyze_network(&network);
+
+    if !analysis.lcm_shortcut_valid {
+        eprintln!("note: the LCM shortcut does not hold for this input");
+    }
+
+    let result = analysis
+        .earliest
+        .expect("ghosts are never on end nodes at the same time");
+    run.finish(result, fingerprint(&input));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::differential::Differential;
+    use aoc_2023::fixture_tests;
+    use aoc_2023::fixtures;
+    use aoc_2023::generate::{self, Options};
+    use aoc_2023::math::lcm;
+
+    fixture_tests!(8, 2, |puzzle: &str| {
+        analyze(puzzle)
+            .earliest
+            .expect("ghosts are never on end nodes at the same time")
+    });
+
+    #[test]
+    fn test_lcm_shortcut_against_long_example() {
+        assert!(analyze(&fixtures::read(8, "example-ghosts")).lcm_shortcut_valid);
+    }
 
-    const SHORT_EXAMPLE: &str = "LLR
+    /// Assumes every ghost is on an end node at exactly the multiples of its
+    /// first hit.
+    fn solve_by_lcm(puzzle: &str) -> u128 {
+        analyze(puzzle)
+            .cycles
+            .iter()
+            .map(|c| c.first_hit().expect("a ghost never gets to an end") as u128)
+            .fold(1, lcm)
+    }
 
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)
-";
+    /// Walks all the ghosts at once until they are all on end nodes.
+    fn solve_by_walking(puzzle: &str) -> u64 {
+        let network = Network::parse(puzzle).unwrap();
+        let mut ghosts: Vec<u32> = network.find_all(&NodePattern::parse("*A")).collect();
+        let mut steps = 0;
 
-    const LONG_EXAMPLE: &str = "LR
+        while !ghosts.iter().all(|g| network.name(*g).ends_with('Z')) {
+            let index = steps % network.directions_len();
+            ghosts.iter_mut().for_each(|g| *g = network.step(*g, index));
+            steps += 1;
+            assert!(steps < 1_000_000, "too many steps");
+        }
 
-11A = (11B, FFF)
-11B = (FFF, 11Z)
-11Z = (11B, FFF)
-22A = (22B, FFF)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-FFF = (FFF, FFF)
-";
+        steps as u64
+    }
 
     #[test]
-    fn test_normalize() {
-        let expected: &str = "LLR
-AAA,BBB,BBB
-BBB,AAA,ZZZ
-ZZZ,ZZZ,ZZZ
-";
-        assert_eq!(normalize(SHORT_EXAMPLE), expected);
+    fn test_variants_agree() {
+        let crt = |puzzle: &str| analyze(puzzle).earliest.unwrap();
+
+        Differential::new(8, 2)
+            .variant("crt", crt)
+            .variant("lcm", solve_by_lcm)
+            .variant("walking", solve_by_walking)
+            .sizes(&[1, 4, 7])
+            .cycles(&[1, 2, 3])
+            .check();
     }
 
     #[test]
-    fn test_solve_against_long_example() {
-        assert_eq!(solve(LONG_EXAMPLE), 6);
+    fn test_variants_agree_without_the_lcm_shortcut() {
+        // With a tail, or goals before the end of the cycles, the first hits
+        // aren't multiples of the cycle lengths anymore.
+        for (tail, goals, goal_offset) in [(3, 1, 0), (0, 1, 2), (2, 3, 1)] {
+            let generated = generate::generate(
+                8,
+                &Options {
+                    cycles: vec![1, 2, 3],
+                    tail,
+                    goals,
+                    goal_offset,
+                    ..Options::sized(1, 4)
+                },
+            )
+            .unwrap();
+            let analysis = analyze(&generated.input);
+
+            assert!(!analysis.lcm_shortcut_valid);
+            assert_ne!(solve_by_lcm(&generated.input), analysis.earliest.unwrap());
+
+            let crt = |puzzle: &str| analyze(puzzle).earliest.unwrap();
+
+            Differential::new(8, 2)
+                .variant("crt", crt)
+                .variant("walking", solve_by_walking)
+                .sizes(&[4, 7])
+                .cycles(&[1, 2, 3])
+                .tail(tail)
+                .goals(goals, goal_offset)
+                .check();
+        }
     }
 }
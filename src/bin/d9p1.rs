@@ -1,45 +1,59 @@
+use aoc_2023::d9::Oasis;
+use aoc_2023::reader;
+use aoc_2023::report::{Hashing, Run};
 use aocd::prelude::*;
+use std::io::BufRead;
 
-fn predict(current: &[i64]) -> i64 {
-    if current.iter().all(|n| *n == 0) {
-        return 0;
-    }
+fn solve(puzzle: impl BufRead) -> i64 {
+    let mut sum: i64 = 0;
+    let mut history = Vec::new();
 
-    let next = current.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
-    predict(&next) + current.last().unwrap()
-}
+    reader::for_each_line(puzzle, |line| {
+        history.clear();
+        history.extend(
+            line.split_whitespace()
+                .map(|n| n.parse::<i64>().expect("not a number")),
+        );
+
+        if history.is_empty() {
+            return;
+        }
+
+        let prediction = Oasis::new(&history)
+            .and_then(|oasis| oasis.extrapolate(1))
+            .unwrap_or_else(|e| panic!("can not predict {:?}: {}", history, e));
+        sum = sum
+            .checked_add(prediction)
+            .expect("the sum of the predictions overflows");
+    })
+    .expect("can not read puzzle");
 
-fn solve(puzzle: &str) -> i64 {
-    puzzle
-        .trim()
-        .lines()
-        .map(|l| {
-            l.split_whitespace()
-                .map(|n| n.parse().expect("not a number"))
-                .collect::<Vec<_>>()
-        })
-        .map(|history| predict(&history))
-        .sum()
+    sum
 }
 
 #[aocd(2023, 9)]
+fn fetch_input() -> String {
+    input!()
+}
+
 fn main() {
-    let input = input!();
-    let result = solve(&input);
-    println!("{}", result);
+    let mut puzzle = Hashing::new(reader::open(std::env::args(), fetch_input));
+    let run = Run::start(env!("CARGO_BIN_NAME"), std::env::args());
+    let result = solve(&mut puzzle);
+    run.finish(result, puzzle.hash());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023::fixture_tests;
 
-    #[test]
-    fn test_solve_against_example() {
-        let puzzle: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45
-";
+    fixture_tests!(9, 1, |puzzle: &str| solve(puzzle.as_bytes()));
 
-        assert_eq!(solve(puzzle), 114);
+    #[test]
+    #[should_panic(expected = "the sum of the predictions overflows")]
+    fn test_sum_overflow() {
+        let line = format!("{0} {0}\n", i64::MAX - 1);
+        solve(line.repeat(2).as_bytes());
     }
 }
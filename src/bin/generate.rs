@@ -0,0 +1,27 @@
+// Makes up puzzle inputs, e.g. `cargo run --bin generate -- 10 --size=5000`.
+// The input goes to stdout and the answers, when known, to stderr, so it can
+// be piped straight into a solution with `--stdin`.
+
+use aoc_2023::generate::{self, Options};
+
+fn main() {
+    let day: u8 = std::env::args()
+        .nth(1)
+        .expect(
+            "usage: generate <day> [--size=<n>] [--seed=<n>] [--cycles=<n>,...] [--tail=<n>] \
+             [--goals=<n>] [--goal-offset=<n>]",
+        )
+        .parse()
+        .expect("day is not a number");
+    let options = Options::from_args(std::env::args().skip(2));
+
+    let generated =
+        generate::generate(day, &options).unwrap_or_else(|| panic!("no generator for day {}", day));
+
+    print!("{}", generated.input);
+    for (part, answer) in generated.answers.iter().enumerate() {
+        if let Some(answer) = answer {
+            eprintln!("part {}: {}", part + 1, answer);
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Checks the puzzle input against what the solutions assume about it,
+// e.g. `cargo run --bin lint -- 7`, or with `--stdin` to check any input.
+// Every problem is printed with its line, and the exit code is 1 if there
+// were any.
+
+use aoc_2023::{lint, reader};
+use aocd::prelude::*;
+use std::io::Read;
+
+fn main() {
+    let day: u8 = std::env::args()
+        .nth(1)
+        .expect("usage: lint <day> [--stdin]")
+        .parse()
+        .expect("day is not a number");
+
+    let mut puzzle = String::new();
+    reader::open(std::env::args(), || Aocd::new(2023, day, None).get_input())
+        .read_to_string(&mut puzzle)
+        .expect("can not read puzzle");
+
+    let problems = lint::lint(day, &puzzle).unwrap_or_else(|| panic!("no checks for day {}", day));
+
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+
+    if !problems.is_empty() {
+        eprintln!("{} problems found", problems.len());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,817 @@
+//! Trebuchet?! (day 1).
+//!
+//! Every calibration line hides digit tokens, either plain digits or spelled
+//! out words, and they may overlap like in `twone` or `eightwo`. Instead of
+//! rewriting the line or trying every token at every offset, all the tokens
+//! of a [`Vocabulary`] are compiled into an Aho-Corasick automaton over bytes
+//! which finds every match, overlapping ones included, in a single pass.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::reader;
+
+/// Languages with built in number words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+    Portuguese,
+}
+
+impl Language {
+    /// Words for zero to nine.
+    pub fn cardinals(self) -> [&'static str; 10] {
+        match self {
+            Self::English => [
+                "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            ],
+            Self::Spanish => [
+                "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+            Self::Portuguese => [
+                "zero", "um", "dois", "três", "quatro", "cinco", "seis", "sete", "oito", "nove",
+            ],
+        }
+    }
+
+    /// Words for first to ninth.
+    pub fn ordinals(self) -> [&'static str; 9] {
+        match self {
+            Self::English => [
+                "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth",
+                "ninth",
+            ],
+            Self::Spanish => [
+                "primero", "segundo", "tercero", "cuarto", "quinto", "sexto", "séptimo", "octavo",
+                "noveno",
+            ],
+            Self::Portuguese => [
+                "primeiro", "segundo", "terceiro", "quarto", "quinto", "sexto", "sétimo", "oitavo",
+                "nono",
+            ],
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = VocabularyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" | "english" => Ok(Self::English),
+            "es" | "spanish" => Ok(Self::Spanish),
+            "pt" | "portuguese" => Ok(Self::Portuguese),
+            _ => Err(VocabularyError::UnknownLanguage(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VocabularyError {
+    UnknownLanguage(String),
+    /// A custom word that isn't written as `word=value`.
+    BadWord(String),
+    /// A custom word whose value isn't a single digit, which calibration
+    /// values are made of.
+    BadValue {
+        word: String,
+        value: u32,
+    },
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownLanguage(name) => write!(f, "unknown language {:?}", name),
+            Self::BadWord(spec) => write!(f, "expected word=value, got {:?}", spec),
+            Self::BadValue { word, value } => {
+                write!(
+                    f,
+                    "{:?} must stand for a digit from 0 to 9, not {}",
+                    word, value
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for VocabularyError {}
+
+/// The tokens a [`Scanner`] looks for, each with the digit it stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    ignore_case: bool,
+}
+
+impl Vocabulary {
+    /// No tokens at all, not even plain digits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plain digits only, as in part 1.
+    pub fn digits() -> Self {
+        (0..10).fold(Self::new(), |v, d| v.push(d.to_string(), d))
+    }
+
+    /// Plain digits and the english words `one` to `nine`, as in part 2.
+    pub fn english() -> Self {
+        Self::digits().with_cardinals(Language::English)
+    }
+
+    /// Adds the words for one to nine. Zero is left out like in the puzzle,
+    /// see [`Vocabulary::with_zero`].
+    pub fn with_cardinals(self, language: Language) -> Self {
+        language.cardinals()[1..]
+            .iter()
+            .zip(1..)
+            .fold(self, |v, (word, value)| v.push(*word, value))
+    }
+
+    pub fn with_zero(self, language: Language) -> Self {
+        self.push(language.cardinals()[0], 0)
+    }
+
+    /// Adds the words for first to ninth.
+    pub fn with_ordinals(self, language: Language) -> Self {
+        language
+            .ordinals()
+            .iter()
+            .zip(1..)
+            .fold(self, |v, (word, value)| v.push(*word, value))
+    }
+
+    fn push(mut self, word: impl Into<String>, value: u32) -> Self {
+        self.words.push((word.into(), value));
+        self
+    }
+
+    /// Adds a custom word standing for `value`, which must be a digit.
+    pub fn with_word(self, word: impl Into<String>, value: u32) -> Result<Self, VocabularyError> {
+        let word = word.into();
+
+        if value > 9 {
+            return Err(VocabularyError::BadValue { word, value });
+        }
+
+        Ok(self.push(word, value))
+    }
+
+    /// Adds comma separated `word=value` pairs, like `pair=2,trio=3`.
+    pub fn with_words(self, spec: &str) -> Result<Self, VocabularyError> {
+        spec.split(',')
+            .filter(|pair| !pair.is_empty())
+            .try_fold(self, |v, pair| {
+                let (word, value) = pair
+                    .split_once('=')
+                    .and_then(|(w, n)| Some((w, n.parse().ok()?)))
+                    .filter(|(w, _)| !w.is_empty())
+                    .ok_or_else(|| VocabularyError::BadWord(pair.to_string()))?;
+
+                v.with_word(word, value)
+            })
+    }
+
+    /// Whether `One` and `ONE` should match `one` too. Letters are compared
+    /// by their simple lowercase form, so it also works for `TRÊS`.
+    pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Builds a vocabulary out of the arguments, starting with the english
+    /// words for one to nine:
+    ///
+    /// - `--language=<en|es|pt>` uses another language's words.
+    /// - `--zero` adds the word for zero.
+    /// - `--ordinals` adds the words for first to ninth.
+    /// - `--words=<word>=<value>,...` adds custom words.
+    /// - `--ignore-case` matches words regardless of case.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, VocabularyError> {
+        let mut language = Language::English;
+        let mut zero = false;
+        let mut ordinals = false;
+        let mut ignore_case = false;
+        let mut custom = Vec::new();
+
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--language=") {
+                language = value.parse()?;
+            } else if let Some(value) = arg.strip_prefix("--words=") {
+                custom.push(value.to_string());
+            } else {
+                match arg.as_str() {
+                    "--zero" => zero = true,
+                    "--ordinals" => ordinals = true,
+                    "--ignore-case" => ignore_case = true,
+                    _ => {}
+                }
+            }
+        }
+
+        let mut vocabulary = Self::digits().with_cardinals(language);
+        if zero {
+            vocabulary = vocabulary.with_zero(language);
+        }
+        if ordinals {
+            vocabulary = vocabulary.with_ordinals(language);
+        }
+        for spec in custom {
+            vocabulary = vocabulary.with_words(&spec)?;
+        }
+
+        Ok(vocabulary.ignore_case(ignore_case))
+    }
+}
+
+/// A match of some token in a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// Byte offset where the token starts.
+    pub start: usize,
+    /// Length in bytes, which may differ from the vocabulary word's when
+    /// ignoring case.
+    pub len: usize,
+    pub value: u32,
+}
+
+impl Token {
+    /// Whether this token starts before `other`, or at the same offset and is
+    /// longer.
+    fn is_before(&self, other: &Token) -> bool {
+        (self.start, std::cmp::Reverse(self.len)) < (other.start, std::cmp::Reverse(other.len))
+    }
+
+    /// Whether this token starts after `other`, or at the same offset and is
+    /// longer. Like [`Token::is_before`], the longest token wins ties.
+    fn is_after(&self, other: &Token) -> bool {
+        (self.start, self.len) > (other.start, other.len)
+    }
+}
+
+/// First and last digit tokens of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    pub first: Token,
+    pub last: Token,
+}
+
+impl Calibration {
+    pub fn value(&self) -> u32 {
+        self.first.value * 10 + self.last.value
+    }
+}
+
+/// A multi-pattern matcher for digit tokens.
+///
+/// The transition table is complete (failure links are already followed at
+/// build time), so scanning costs one table lookup per byte plus the matches
+/// found.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    delta: Vec<[u32; 256]>,
+    /// Every `(len, value)` token ending at each state, including the ones
+    /// reachable through failure links. Lengths are in folded bytes.
+    outputs: Vec<Vec<(usize, u32)>>,
+    ignore_case: bool,
+}
+
+const ROOT: u32 = 0;
+
+/// The simple lowercase form of `c`, or `c` itself if it lowercases to more
+/// than one char.
+fn fold(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+impl Scanner {
+    /// Builds the automaton for a vocabulary. Empty words are ignored.
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let mut delta = vec![[ROOT; 256]];
+        let mut outputs = vec![Vec::new()];
+
+        // Trie. No edge goes back to the root, so `ROOT` doubles as "no edge".
+        for (word, value) in &vocabulary.words {
+            let word: String = if vocabulary.ignore_case {
+                word.chars().map(fold).collect()
+            } else {
+                word.clone()
+            };
+            if word.is_empty() {
+                continue;
+            }
+
+            let mut state = ROOT;
+            for &b in word.as_bytes() {
+                if delta[state as usize][b as usize] == ROOT {
+                    delta.push([ROOT; 256]);
+                    outputs.push(Vec::new());
+                    delta[state as usize][b as usize] = (delta.len() - 1) as u32;
+                }
+                state = delta[state as usize][b as usize];
+            }
+
+            outputs[state as usize].push((word.len(), *value));
+        }
+
+        // Breadth first, so the failure target of a state is always complete
+        // before the state itself is.
+        let mut fail = vec![ROOT; delta.len()];
+        let mut queue: VecDeque<u32> = delta[ROOT as usize]
+            .iter()
+            .copied()
+            .filter(|&s| s != ROOT)
+            .collect();
+
+        while let Some(state) = queue.pop_front() {
+            let fallback = fail[state as usize];
+            let inherited = outputs[fallback as usize].clone();
+            outputs[state as usize].extend(inherited);
+
+            let fallback_row = delta[fallback as usize];
+            for (next, fallback_next) in delta[state as usize].iter_mut().zip(fallback_row) {
+                if *next == ROOT {
+                    *next = fallback_next;
+                } else {
+                    fail[*next as usize] = fallback_next;
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        Self {
+            delta,
+            outputs,
+            ignore_case: vocabulary.ignore_case,
+        }
+    }
+
+    /// Same as `Scanner::new(&Vocabulary::digits())`.
+    pub fn digits() -> Self {
+        Self::new(&Vocabulary::digits())
+    }
+
+    /// Same as `Scanner::new(&Vocabulary::english())`.
+    pub fn spelled() -> Self {
+        Self::new(&Vocabulary::english())
+    }
+
+    /// Calls `found` with every token in `line`, ordered by where they end.
+    pub fn for_each_token(&self, line: &str, mut found: impl FnMut(Token)) {
+        let mut state = ROOT;
+
+        if !self.ignore_case {
+            for (i, b) in line.bytes().enumerate() {
+                state = self.delta[state as usize][b as usize];
+                for &(len, value) in &self.outputs[state as usize] {
+                    found(Token {
+                        start: i + 1 - len,
+                        len,
+                        value,
+                    });
+                }
+            }
+            return;
+        }
+
+        // Folding may change how many bytes a char takes, so remember where
+        // the char of every folded byte started in the original line.
+        let mut starts = Vec::with_capacity(line.len());
+        let mut buffer = [0; 4];
+
+        for (i, c) in line.char_indices() {
+            let end = i + c.len_utf8();
+            for &b in fold(c).encode_utf8(&mut buffer).as_bytes() {
+                starts.push(i);
+                state = self.delta[state as usize][b as usize];
+                for &(len, value) in &self.outputs[state as usize] {
+                    let start = starts[starts.len() - len];
+                    found(Token {
+                        start,
+                        len: end - start,
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Every token in `line`, ordered by where they end.
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        self.for_each_token(line, |token| tokens.push(token));
+        tokens
+    }
+
+    /// The first and last tokens of `line`, or `None` if there's none.
+    pub fn scan(&self, line: &str) -> Option<Calibration> {
+        let mut found: Option<Calibration> = None;
+
+        self.for_each_token(line, |token| {
+            found = Some(match found {
+                None => Calibration {
+                    first: token,
+                    last: token,
+                },
+                Some(Calibration { first, last }) => Calibration {
+                    first: if token.is_before(&first) {
+                        token
+                    } else {
+                        first
+                    },
+                    last: if token.is_after(&last) { token } else { last },
+                },
+            });
+        });
+
+        found
+    }
+}
+
+/// What to do with a line where no digit token is found, like a blank line
+/// left over from concatenating or editing inputs by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingDigits {
+    /// Fail with the line number, the puzzle never has such lines.
+    #[default]
+    Error,
+    /// Leave the line out of the sum.
+    Skip,
+    /// Count the line as a calibration value of zero.
+    Zero,
+}
+
+/// A `--no-digits` policy that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPolicy(pub String);
+
+impl fmt::Display for UnknownPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown --no-digits policy {:?}, expected error, skip or zero",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownPolicy {}
+
+impl std::str::FromStr for MissingDigits {
+    type Err = UnknownPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "skip" => Ok(Self::Skip),
+            "zero" => Ok(Self::Zero),
+            _ => Err(UnknownPolicy(s.to_string())),
+        }
+    }
+}
+
+impl MissingDigits {
+    /// Looks for `--no-digits=<error|skip|zero>` in the arguments, `Error`
+    /// if there's none.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, UnknownPolicy> {
+        args.find_map(|arg| arg.strip_prefix("--no-digits=").map(str::parse))
+            .unwrap_or(Ok(Self::Error))
+    }
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    /// No digit token in the given line, counting from 1.
+    NoDigits {
+        line: usize,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoDigits { line } => write!(f, "no digits in line {}", line),
+            Self::Io(e) => write!(f, "can not read the document: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+impl From<io::Error> for CalibrationError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// The result of calibrating a whole document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub sum: u64,
+    pub lines: usize,
+    /// Lines whose calibration value went into the sum, including those
+    /// without digits counted as zero.
+    pub counted: usize,
+    /// Lines without digits, which were skipped or counted as zero
+    /// depending on the policy.
+    pub missing: usize,
+    pub policy: MissingDigits,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let handling = match self.policy {
+            MissingDigits::Skip => "skipped",
+            MissingDigits::Zero => "counted as zero",
+            MissingDigits::Error => "rejected",
+        };
+
+        write!(
+            f,
+            "{} of {} lines had no digits and were {}",
+            self.missing, self.lines, handling
+        )
+    }
+}
+
+/// Sums the calibration values of every line, as given by `value`, handling
+/// lines without any digit according to `policy`. The document is read one
+/// line at a time.
+pub fn calibrate(
+    document: impl BufRead,
+    policy: MissingDigits,
+    mut value: impl FnMut(&str) -> Option<u32>,
+) -> Result<Summary, CalibrationError> {
+    let mut summary = Summary {
+        sum: 0,
+        lines: 0,
+        counted: 0,
+        missing: 0,
+        policy,
+    };
+
+    reader::try_for_each_line(document, |line| {
+        summary.lines += 1;
+
+        match (value(line), policy) {
+            (Some(value), _) => {
+                summary.sum += value as u64;
+                summary.counted += 1;
+            }
+            (None, MissingDigits::Error) => {
+                return Err(CalibrationError::NoDigits {
+                    line: summary.lines,
+                })
+            }
+            (None, MissingDigits::Skip) => summary.missing += 1,
+            (None, MissingDigits::Zero) => {
+                summary.missing += 1;
+                summary.counted += 1;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = Scanner::spelled();
+        let value = |line| scanner.scan(line).map(|c| c.value());
+
+        assert_eq!(value("twone"), Some(21));
+        assert_eq!(value("eightwo"), Some(82));
+        assert_eq!(value("oneight"), Some(18));
+        assert_eq!(value("sevenine"), Some(79));
+        assert_eq!(value("xtwone3four"), Some(24));
+        assert_eq!(value("7pqrstsixteen"), Some(76));
+        assert_eq!(value("treb7uchet"), Some(77));
+        assert_eq!(value("abc"), None);
+        assert_eq!(value(""), None);
+    }
+
+    #[test]
+    fn test_token_offsets() {
+        let scanner = Scanner::spelled();
+        let calibration = scanner.scan("zoneight234").unwrap();
+
+        assert_eq!(
+            calibration.first,
+            Token {
+                start: 1,
+                len: 3,
+                value: 1
+            }
+        );
+        assert_eq!(
+            calibration.last,
+            Token {
+                start: 10,
+                len: 1,
+                value: 4
+            }
+        );
+        assert_eq!(scanner.tokens("zoneight234").len(), 5);
+    }
+
+    #[test]
+    fn test_nested_tokens() {
+        // Tokens that contain each other, which english digits never do.
+        let vocabulary = Vocabulary::new()
+            .with_words("seventeen=1,seven=7,teen=8,even=2")
+            .unwrap();
+        let scanner = Scanner::new(&vocabulary);
+        let calibration = scanner.scan("seventeen").unwrap();
+
+        assert_eq!(calibration.first.value, 1);
+        assert_eq!(calibration.last.value, 8);
+
+        // The longest token wins when several start at the last offset too.
+        let vocabulary = Vocabulary::new().with_words("ab=1,abc=2").unwrap();
+        let calibration = Scanner::new(&vocabulary).scan("abc").unwrap();
+        assert_eq!((calibration.first.value, calibration.last.value), (2, 2));
+    }
+
+    #[test]
+    fn test_digits_only() {
+        let scanner = Scanner::digits();
+
+        assert_eq!(scanner.scan("a1b2c3d4e5f").map(|c| c.value()), Some(15));
+        assert_eq!(scanner.scan("twone").map(|c| c.value()), None);
+    }
+
+    #[test]
+    fn test_languages() {
+        let value =
+            |vocabulary: &Vocabulary, line| Scanner::new(vocabulary).scan(line).map(|c| c.value());
+
+        let spanish = Vocabulary::digits().with_cardinals(Language::Spanish);
+        assert_eq!(value(&spanish, "xdoseisx"), Some(26));
+        assert_eq!(value(&spanish, "onetwo"), None);
+
+        let portuguese = Vocabulary::digits().with_cardinals(Language::Portuguese);
+        assert_eq!(value(&portuguese, "trêsnoveum"), Some(31));
+
+        let english = Vocabulary::english();
+        assert_eq!(value(&english, "zero0"), Some(0));
+        assert_eq!(value(&english, "zerone"), Some(11));
+        assert_eq!(
+            value(&english.clone().with_zero(Language::English), "zerone"),
+            Some(1)
+        );
+
+        let ordinals = Vocabulary::new().with_ordinals(Language::English);
+        assert_eq!(value(&ordinals, "fourthirdfirst"), Some(41));
+        assert_eq!(value(&ordinals, "eighth"), Some(88));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let value =
+            |vocabulary: Vocabulary, line| Scanner::new(&vocabulary).scan(line).map(|c| c.value());
+
+        assert_eq!(value(Vocabulary::english(), "TwOne"), None);
+        assert_eq!(
+            value(Vocabulary::english().ignore_case(true), "TwOne"),
+            Some(21)
+        );
+
+        let portuguese = Vocabulary::new()
+            .with_cardinals(Language::Portuguese)
+            .ignore_case(true);
+        assert_eq!(value(portuguese.clone(), "TRÊS"), Some(33));
+
+        // The Kelvin sign folds to a one byte `k`, offsets are still in the
+        // original line.
+        let kelvin = Vocabulary::new()
+            .with_word("ok", 1)
+            .unwrap()
+            .ignore_case(true);
+        let tokens = Scanner::new(&kelvin).tokens("xo\u{212A}");
+        assert_eq!(
+            tokens,
+            vec![Token {
+                start: 1,
+                len: 4,
+                value: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_vocabulary_from_args() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            Vocabulary::from_args(args(&["d1p2"])),
+            Ok(Vocabulary::english())
+        );
+        assert_eq!(
+            Vocabulary::from_args(args(&[
+                "d1p2",
+                "--language=es",
+                "--zero",
+                "--words=par=2",
+                "--ignore-case"
+            ])),
+            Ok(Vocabulary::digits()
+                .with_cardinals(Language::Spanish)
+                .with_zero(Language::Spanish)
+                .with_word("par", 2)
+                .unwrap()
+                .ignore_case(true))
+        );
+        assert_eq!(
+            Vocabulary::from_args(args(&["d1p2", "--language=fr"])),
+            Err(VocabularyError::UnknownLanguage("fr".to_string()))
+        );
+        assert_eq!(
+            Vocabulary::new().with_words("one=1,two"),
+            Err(VocabularyError::BadWord("two".to_string()))
+        );
+        assert_eq!(
+            Vocabulary::from_args(args(&["d1p2", "--words=docena=12"])),
+            Err(VocabularyError::BadValue {
+                word: "docena".to_string(),
+                value: 12
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_digits() {
+        let scanner = Scanner::digits();
+        let document = "a1b\n\nno digits\n7\n";
+        let calibrate = |policy| {
+            calibrate(document.as_bytes(), policy, |l| {
+                scanner.scan(l).map(|c| c.value())
+            })
+        };
+
+        assert!(matches!(
+            calibrate(MissingDigits::Error),
+            Err(CalibrationError::NoDigits { line: 2 })
+        ));
+
+        let skipped = calibrate(MissingDigits::Skip).unwrap();
+        assert_eq!(
+            (skipped.sum, skipped.lines, skipped.counted, skipped.missing),
+            (88, 4, 2, 2)
+        );
+        assert_eq!(
+            skipped.to_string(),
+            "2 of 4 lines had no digits and were skipped"
+        );
+
+        let zeroed = calibrate(MissingDigits::Zero).unwrap();
+        assert_eq!(
+            (zeroed.sum, zeroed.lines, zeroed.counted, zeroed.missing),
+            (88, 4, 4, 2)
+        );
+        assert_eq!(
+            zeroed.to_string(),
+            "2 of 4 lines had no digits and were counted as zero"
+        );
+    }
+
+    #[test]
+    fn test_missing_digits_from_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            MissingDigits::from_args(args(&["d1p1"]).into_iter()),
+            Ok(MissingDigits::Error)
+        );
+        assert_eq!(
+            MissingDigits::from_args(args(&["d1p1", "--no-digits=skip"]).into_iter()),
+            Ok(MissingDigits::Skip)
+        );
+        assert_eq!(
+            MissingDigits::from_args(args(&["d1p1", "--no-digits=zero"]).into_iter()),
+            Ok(MissingDigits::Zero)
+        );
+        assert_eq!(
+            MissingDigits::from_args(args(&["d1p1", "--no-digits=skpi"]).into_iter()),
+            Err(UnknownPolicy("skpi".to_string()))
+        );
+    }
+}
@@ -0,0 +1,556 @@
+//! Pipe Maze (day 10).
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::grid::{Grid, Palette};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Pos {
+    pub fn new(x: i64, y: i64) -> Self {
+        Pos { x, y }
+    }
+
+    pub fn up(&self) -> Self {
+        Self {
+            y: self.y - 1,
+            ..*self
+        }
+    }
+
+    pub fn down(&self) -> Self {
+        Self {
+            y: self.y + 1,
+            ..*self
+        }
+    }
+
+    pub fn left(&self) -> Self {
+        Self {
+            x: self.x - 1,
+            ..*self
+        }
+    }
+
+    pub fn right(&self) -> Self {
+        Self {
+            x: self.x + 1,
+            ..*self
+        }
+    }
+
+    pub fn neighbors(&self) -> [Self; 4] {
+        [self.up(), self.down(), self.left(), self.right()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub absolute_pos: Pos,
+    pub connections: [Pos; 2],
+    pub raw: char,
+}
+
+impl Tile {
+    pub fn from(raw: char, x: i64, y: i64) -> Self {
+        let pos = Pos::new(x, y);
+
+        let connections = match raw {
+            '|' => [pos.down(), pos.up()],
+            '-' => [pos.left(), pos.right()],
+            'L' => [pos.up(), pos.right()],
+            'J' => [pos.up(), pos.left()],
+            '7' => [pos.down(), pos.left()],
+            'F' => [pos.down(), pos.right()],
+            _ => [pos, pos],
+        };
+
+        Self {
+            raw,
+            connections,
+            absolute_pos: pos,
+        }
+    }
+}
+
+const PIPES: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
+
+/// 256 colour palette ramp from blue (close to the start) to red (far).
+const DISTANCE_COLORS: [u8; 11] = [21, 27, 33, 39, 45, 51, 46, 118, 226, 208, 196];
+
+fn heavy_box(raw: char) -> char {
+    match raw {
+        '|' => '┃',
+        '-' => '━',
+        'L' => '┗',
+        'J' => '┛',
+        '7' => '┓',
+        'F' => '┏',
+        _ => raw,
+    }
+}
+
+fn light_box(raw: char) -> char {
+    match raw {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        _ => raw,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderOptions {
+    /// Use ANSI escape codes. Junk pipes are dimmed and the loop is bold.
+    pub color: bool,
+    /// Mark the tiles enclosed by the loop.
+    pub enclosed: bool,
+    /// Colour the loop by distance from the start, only with `color`.
+    pub distances: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileClass {
+    /// Pipe that isn't part of the loop.
+    Junk,
+    Loop,
+    Start,
+    Enclosed,
+}
+
+impl TileClass {
+    pub fn palette() -> Palette<Self> {
+        Palette::new([20, 20, 20])
+            .with(Self::Junk, [70, 70, 70])
+            .with(Self::Loop, [0, 170, 255])
+            .with(Self::Start, [255, 60, 60])
+            .with(Self::Enclosed, [255, 200, 0])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    NoStart,
+    /// The pipe under `S` can't be inferred because it doesn't have exactly
+    /// two neighbours connecting to it.
+    StartConnections(usize),
+    /// The loop leads to a position without a pipe.
+    DeadEnd(Pos),
+    /// A pipe connects to another one that doesn't connect back.
+    OneWay {
+        from: Pos,
+        to: Pos,
+    },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoStart => write!(f, "no starting tile found"),
+            Self::StartConnections(count) => write!(
+                f,
+                "starting tile has {} connecting neighbours instead of 2",
+                count
+            ),
+            Self::DeadEnd(pos) => write!(f, "the loop ends at ({}, {})", pos.x, pos.y),
+            Self::OneWay { from, to } => write!(
+                f,
+                "({}, {}) connects to ({}, {}) but not the other way around",
+                from.x, from.y, to.x, to.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+/// The grid of tiles, with the pipe hidden under `S` already put in place.
+#[derive(Debug, Clone)]
+pub struct Maze {
+    pub tiles: HashMap<Pos, Tile>,
+    pub start: Pos,
+}
+
+impl Maze {
+    pub fn parse(puzzle: &str) -> Result<Self, MazeError> {
+        let mut tiles: HashMap<Pos, Tile> = puzzle
+            .trim()
+            .lines()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.chars()
+                    .enumerate()
+                    .map(move |(x, c)| Tile::from(c, x as i64, y as i64))
+            })
+            .map(|t| (t.absolute_pos, t))
+            .collect();
+
+        let start = tiles
+            .values()
+            .find(|t| t.raw == 'S')
+            .ok_or(MazeError::NoStart)?
+            .absolute_pos;
+
+        let connected: Vec<Pos> = start
+            .neighbors()
+            .into_iter()
+            .filter(|p| tiles.get(p).is_some_and(|t| t.connections.contains(&start)))
+            .collect();
+
+        let pipe = PIPES
+            .iter()
+            .map(|c| Tile::from(*c, start.x, start.y))
+            .find(|t| connected.len() == 2 && connected.iter().all(|p| t.connections.contains(p)))
+            .ok_or(MazeError::StartConnections(connected.len()))?;
+
+        tiles.insert(start, pipe);
+
+        Ok(Self { tiles, start })
+    }
+
+    /// Where `from` leads to through `to`, which must be a pipe connecting
+    /// back to `from`.
+    fn follow(&self, from: Pos, to: Pos) -> Result<Pos, MazeError> {
+        let tile = self
+            .tiles
+            .get(&to)
+            .filter(|t| t.connections != [to, to])
+            .ok_or(MazeError::DeadEnd(to))?;
+
+        if !tile.connections.contains(&from) {
+            return Err(MazeError::OneWay { from, to });
+        }
+
+        Ok(tile
+            .connections
+            .into_iter()
+            .find(|p| *p != from)
+            .unwrap_or(from))
+    }
+
+    /// Positions of the loop that goes through the start, in order and
+    /// starting with it.
+    pub fn main_loop(&self) -> Result<Vec<Pos>, MazeError> {
+        let mut path = vec![self.start];
+        let mut previous = self.start;
+        let mut current = self.tiles[&self.start].connections[0];
+
+        while current != self.start {
+            path.push(current);
+            (previous, current) = (current, self.follow(previous, current)?);
+        }
+
+        Ok(path)
+    }
+
+    /// Steps needed to go from the start to every tile of the loop, walking
+    /// both ways around it at once.
+    pub fn distances(&self) -> Result<HashMap<Pos, usize>, MazeError> {
+        let mut distances = HashMap::from([(self.start, 0)]);
+        let mut queue = VecDeque::from([self.start]);
+
+        while let Some(pos) = queue.pop_front() {
+            let distance = distances[&pos];
+
+            for next in self.tiles[&pos].connections {
+                // Validates the connection even if `next` was already seen.
+                self.follow(pos, next)?;
+
+                if let Entry::Vacant(entry) = distances.entry(next) {
+                    entry.insert(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Ok(distances)
+    }
+
+    /// The loop tile farthest from the start and its distance. Ties go to
+    /// the topmost, then leftmost, tile.
+    pub fn farthest(&self) -> Result<(Pos, usize), MazeError> {
+        let distances = self.distances()?;
+
+        let farthest = distances
+            .into_iter()
+            .max_by_key(|(pos, distance)| (*distance, -pos.y, -pos.x))
+            .expect("distances always include the start");
+
+        Ok(farthest)
+    }
+
+    /// Largest x and y of the grid.
+    fn max_pos(&self) -> Pos {
+        Pos::new(
+            self.tiles.keys().map(|p| p.x).max().unwrap_or(0),
+            self.tiles.keys().map(|p| p.y).max().unwrap_or(0),
+        )
+    }
+
+    /// Tiles enclosed by the loop, scanning every row and flipping between
+    /// inside and outside when crossing a loop tile that goes up.
+    pub fn enclosed_tiles(&self, path: &[Pos]) -> HashSet<Pos> {
+        let on_loop: HashSet<Pos> = path.iter().copied().collect();
+        let max = self.max_pos();
+
+        let mut enclosed = HashSet::new();
+
+        for y in 0..=max.y {
+            let mut inside = false;
+
+            for x in 0..=max.x {
+                let pos = Pos::new(x, y);
+
+                if on_loop.contains(&pos) {
+                    if self.tiles[&pos].connections.contains(&pos.up()) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed.insert(pos);
+                }
+            }
+        }
+
+        enclosed
+    }
+
+    pub fn enclosed_by_scanline(&self, path: &[Pos]) -> i64 {
+        self.enclosed_tiles(path).len() as i64
+    }
+
+    /// Draws the maze with box-drawing characters. Loop pipes are heavy and
+    /// the rest light, enclosed tiles are dots and the start is an `S`
+    /// unless colours are used, in which case it's highlighted instead.
+    pub fn render(&self, options: &RenderOptions) -> Result<String, MazeError> {
+        let path = self.main_loop()?;
+        let on_loop: HashSet<Pos> = path.iter().copied().collect();
+        let distances = self.distances()?;
+        let max_distance = distances.values().copied().max().unwrap_or(0).max(1);
+
+        let enclosed = if options.enclosed {
+            self.enclosed_tiles(&path)
+        } else {
+            HashSet::new()
+        };
+
+        let max = self.max_pos();
+        let mut output = String::new();
+
+        for y in 0..=max.y {
+            for x in 0..=max.x {
+                let pos = Pos::new(x, y);
+                let raw = self.tiles.get(&pos).map(|t| t.raw).unwrap_or('.');
+
+                let (glyph, style) = if on_loop.contains(&pos) {
+                    let style = if pos == self.start {
+                        "1;7".to_string()
+                    } else if options.distances {
+                        let color = DISTANCE_COLORS
+                            [distances[&pos] * (DISTANCE_COLORS.len() - 1) / max_distance];
+                        format!("38;5;{}", color)
+                    } else {
+                        "1".to_string()
+                    };
+
+                    if pos == self.start && !options.color {
+                        ('S', style)
+                    } else {
+                        (heavy_box(raw), style)
+                    }
+                } else if enclosed.contains(&pos) {
+                    ('•', "33".to_string())
+                } else if PIPES.contains(&raw) {
+                    (light_box(raw), "2".to_string())
+                } else {
+                    (' ', String::new())
+                };
+
+                if options.color && !style.is_empty() {
+                    output.push_str(&format!("\x1b[{}m{}\x1b[0m", style, glyph));
+                } else {
+                    output.push(glyph);
+                }
+            }
+
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+
+    /// Classifies every tile for image export.
+    pub fn to_grid(&self) -> Result<Grid<TileClass>, MazeError> {
+        let path = self.main_loop()?;
+        let max = self.max_pos();
+        let mut grid = Grid::new(max.x as usize + 1, max.y as usize + 1);
+
+        for tile in self.tiles.values() {
+            if PIPES.contains(&tile.raw) {
+                let pos = tile.absolute_pos;
+                grid.set(pos.x as usize, pos.y as usize, TileClass::Junk);
+            }
+        }
+
+        for pos in self.enclosed_tiles(&path) {
+            grid.set(pos.x as usize, pos.y as usize, TileClass::Enclosed);
+        }
+
+        for pos in path.iter() {
+            grid.set(pos.x as usize, pos.y as usize, TileClass::Loop);
+        }
+
+        grid.set(
+            self.start.x as usize,
+            self.start.y as usize,
+            TileClass::Start,
+        );
+
+        Ok(grid)
+    }
+}
+
+/// Tiles enclosed by the loop, using the shoelace formula to get the area of
+/// the polygon and Pick's theorem to go from that area to inner points.
+pub fn enclosed_by_area(path: &[Pos]) -> i64 {
+    let twice_area: i64 = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+
+    // A = I + B / 2 - 1
+    twice_area.abs() / 2 - path.len() as i64 / 2 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_start_pipe() {
+        let maze = Maze::parse(
+            "
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+",
+        )
+        .unwrap();
+
+        assert_eq!(maze.start, Pos::new(0, 2));
+        assert_eq!(maze.tiles[&maze.start].raw, 'F');
+
+        let maze = Maze::parse(".....\n.F-7.\n.|.|.\n.L-S.\n.....").unwrap();
+        assert_eq!(maze.tiles[&maze.start].raw, 'J');
+    }
+
+    #[test]
+    fn test_main_loop() {
+        let maze = Maze::parse("S-7\n|.|\nL-J").unwrap();
+        let path = maze.main_loop().unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[0], Pos::new(0, 0));
+        assert_eq!(path[1], Pos::new(0, 1));
+        assert_eq!(path[7], Pos::new(1, 0));
+    }
+
+    #[test]
+    fn test_main_loop_errors() {
+        assert_eq!(
+            Maze::parse("S7\nL.").unwrap().main_loop(),
+            Err(MazeError::DeadEnd(Pos::new(1, 1)))
+        );
+        assert_eq!(
+            Maze::parse("S-7\nL|J").unwrap().main_loop(),
+            Err(MazeError::OneWay {
+                from: Pos::new(0, 1),
+                to: Pos::new(1, 1)
+            })
+        );
+        assert_eq!(
+            Maze::parse("S-7\nL|J").unwrap().distances(),
+            Err(MazeError::OneWay {
+                from: Pos::new(0, 1),
+                to: Pos::new(1, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let maze = Maze::parse("S-7\n|.|\nL-J").unwrap();
+        let distances = maze.distances().unwrap();
+
+        assert_eq!(distances.len(), 8);
+        assert_eq!(distances[&Pos::new(1, 0)], 1);
+        assert_eq!(distances[&Pos::new(2, 1)], 3);
+        assert_eq!(maze.farthest(), Ok((Pos::new(2, 2), 4)));
+    }
+
+    #[test]
+    fn test_render() {
+        let maze = Maze::parse(".....\n.S-7.\n.|.|.\n.L-J7\n.....").unwrap();
+
+        let options = RenderOptions {
+            enclosed: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            maze.render(&options).unwrap(),
+            "     \n S━┓ \n ┃•┃ \n ┗━┛┐\n     \n"
+        );
+
+        let options = RenderOptions {
+            color: true,
+            ..Default::default()
+        };
+
+        let rendered = maze.render(&options).unwrap();
+        assert!(rendered.contains("\x1b[1;7m┏\x1b[0m"));
+        assert!(rendered.contains("\x1b[2m┐\x1b[0m"));
+        assert!(rendered.contains("\x1b[1m━\x1b[0m"));
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let maze = Maze::parse(".....\n.S-7.\n.|.|.\n.L-J7\n.....").unwrap();
+        let grid = maze.to_grid().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (5, 5));
+        assert_eq!(grid.get(0, 0), None);
+        assert_eq!(grid.get(1, 1), Some(TileClass::Start));
+        assert_eq!(grid.get(2, 1), Some(TileClass::Loop));
+        assert_eq!(grid.get(2, 2), Some(TileClass::Enclosed));
+        assert_eq!(grid.get(4, 3), Some(TileClass::Junk));
+    }
+
+    #[test]
+    fn test_infer_start_pipe_errors() {
+        assert_eq!(Maze::parse("...\n...").unwrap_err(), MazeError::NoStart);
+        assert_eq!(
+            Maze::parse("...\n.S.\n...").unwrap_err(),
+            MazeError::StartConnections(0)
+        );
+        assert_eq!(
+            Maze::parse(".|.\n-S-\n.|.").unwrap_err(),
+            MazeError::StartConnections(4)
+        );
+        assert_eq!(
+            Maze::parse(".|.\n-S-\n...").unwrap_err(),
+            MazeError::StartConnections(3)
+        );
+    }
+}
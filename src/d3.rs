@@ -0,0 +1,91 @@
+//! Gear Ratios (day 3).
+
+use crate::grid::{Grid, Palette};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchematicClass {
+    Symbol,
+    /// Digit of a number that isn't next to any symbol.
+    Number,
+    /// Digit of a number next to a symbol.
+    PartNumber,
+}
+
+impl SchematicClass {
+    pub fn palette() -> Palette<Self> {
+        Palette::new([15, 15, 35])
+            .with(Self::Symbol, [255, 255, 102])
+            .with(Self::Number, [90, 90, 110])
+            .with(Self::PartNumber, [0, 153, 0])
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.' && c != ' '
+}
+
+/// Classifies every cell of the schematic for image export.
+pub fn to_grid(puzzle: &str) -> Grid<SchematicClass> {
+    let rows: Vec<Vec<char>> = puzzle.trim().lines().map(|l| l.chars().collect()).collect();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut grid = Grid::new(width, rows.len());
+
+    let symbol_at = |x: usize, y: usize| {
+        rows.get(y)
+            .and_then(|r| r.get(x))
+            .is_some_and(|c| is_symbol(*c))
+    };
+
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0;
+
+        while x < row.len() {
+            if is_symbol(row[x]) {
+                grid.set(x, y, SchematicClass::Symbol);
+            }
+
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+
+            let start = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
+            }
+
+            let is_part = (y.saturating_sub(1)..=y + 1)
+                .any(|ny| (start.saturating_sub(1)..=x).any(|nx| symbol_at(nx, ny)));
+
+            let class = if is_part {
+                SchematicClass::PartNumber
+            } else {
+                SchematicClass::Number
+            };
+
+            for digit_x in start..x {
+                grid.set(digit_x, y, class);
+            }
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_grid() {
+        let grid = to_grid("467..114..\n...*......\n");
+
+        assert_eq!(grid.width(), 10);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(SchematicClass::PartNumber));
+        assert_eq!(grid.get(2, 0), Some(SchematicClass::PartNumber));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(5, 0), Some(SchematicClass::Number));
+        assert_eq!(grid.get(3, 1), Some(SchematicClass::Symbol));
+    }
+}
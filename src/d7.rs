@@ -0,0 +1,836 @@
+//! Camel Cards (day 7). Both parts rank hands the same way and only differ in
+//! the [`Rules`] they use.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::reader;
+
+/// A card rank under some [`Rules`]. Higher ranks are stronger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card(u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandKind {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+    /// Only reachable with hands of six or more cards.
+    SixOfAKind,
+}
+
+impl HandKind {
+    /// Kind of a hand given the sizes of its two biggest groups of equal
+    /// cards. Groups bigger than six still count as six of a kind.
+    fn from_group_sizes(biggest: usize, second_biggest: usize) -> Self {
+        match (biggest, second_biggest) {
+            (6.., _) => Self::SixOfAKind,
+            (5, _) => Self::FiveOfAKind,
+            (4, _) => Self::FourOfAKind,
+            (3, 2..) => Self::FullHouse,
+            (3, _) => Self::ThreeOfAKind,
+            (2, 2..) => Self::TwoPair,
+            (2, _) => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+/// How to break ties between hands of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare the first card of each hand, then the second, and so on.
+    Positional,
+    /// Compare the strongest card of each hand, then the second strongest,
+    /// and so on, regardless of where they are in the hand.
+    BestCard,
+}
+
+impl std::str::FromStr for TieBreak {
+    type Err = RulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "positional" => Ok(Self::Positional),
+            "best-card" => Ok(Self::BestCard),
+            _ => Err(RulesError::UnknownTieBreak(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    DuplicatedLabel(char),
+    /// Card ranks are a `u8`, so there can't be more labels than that.
+    TooManyLabels(usize),
+    WildNotACard(char),
+    UnknownTieBreak(String),
+    /// Not a positive number.
+    InvalidHandSize(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DuplicatedLabel(label) => {
+                write!(f, "card label {:?} appears twice in the card order", label)
+            }
+            Self::TooManyLabels(count) => write!(
+                f,
+                "{} card labels, but there can't be more than {}",
+                count,
+                u8::MAX
+            ),
+            Self::WildNotACard(label) => {
+                write!(f, "wild card {:?} is not in the card order", label)
+            }
+            Self::UnknownTieBreak(name) => write!(
+                f,
+                "unknown tie-break {:?}, expected positional or best-card",
+                name
+            ),
+            Self::InvalidHandSize(size) => {
+                write!(f, "hand size {:?} is not a positive number", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Card labels from the weakest to the strongest.
+    order: Vec<char>,
+    /// Labels that act as whatever card makes the hand strongest.
+    wild: Vec<char>,
+    tie_break: TieBreak,
+    hand_size: usize,
+}
+
+impl Rules {
+    /// Rules with the given card order (weakest first), no wild cards,
+    /// positional tie-breaks and hands of five cards.
+    pub fn new(order: &str) -> Result<Self, RulesError> {
+        let order: Vec<char> = order.chars().collect();
+
+        for (i, label) in order.iter().enumerate() {
+            if order[..i].contains(label) {
+                return Err(RulesError::DuplicatedLabel(*label));
+            }
+        }
+
+        if order.len() > u8::MAX as usize {
+            return Err(RulesError::TooManyLabels(order.len()));
+        }
+
+        Ok(Self {
+            order,
+            wild: Vec::new(),
+            tie_break: TieBreak::Positional,
+            hand_size: 5,
+        })
+    }
+
+    /// Part 1 rules.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA").unwrap()
+    }
+
+    /// Part 2 rules, where `J` is the weakest card but acts as any other.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA")
+            .and_then(|rules| rules.with_wild("J"))
+            .unwrap()
+    }
+
+    pub fn with_wild(mut self, labels: &str) -> Result<Self, RulesError> {
+        if let Some(label) = labels.chars().find(|l| !self.order.contains(l)) {
+            return Err(RulesError::WildNotACard(label));
+        }
+
+        self.wild = labels.chars().collect();
+        Ok(self)
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn with_hand_size(mut self, hand_size: usize) -> Self {
+        self.hand_size = hand_size;
+        self
+    }
+
+    /// `defaults` changed by the flags in the arguments:
+    ///
+    /// - `--cards=<labels>` sets the card order, weakest first.
+    /// - `--wild=<labels>` sets the wild cards, none if empty.
+    /// - `--tie-break=<positional|best-card>` sets how ties are broken.
+    /// - `--hand-size=<n>` sets how many cards a hand has.
+    pub fn from_args(
+        args: impl Iterator<Item = String>,
+        defaults: &Self,
+    ) -> Result<Self, RulesError> {
+        let mut order: String = defaults.order.iter().collect();
+        let mut wild: String = defaults.wild.iter().collect();
+        let mut tie_break = defaults.tie_break;
+        let mut hand_size = defaults.hand_size;
+
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--cards=") {
+                order = value.to_string();
+            } else if let Some(value) = arg.strip_prefix("--wild=") {
+                wild = value.to_string();
+            } else if let Some(value) = arg.strip_prefix("--tie-break=") {
+                tie_break = value.parse()?;
+            } else if let Some(value) = arg.strip_prefix("--hand-size=") {
+                hand_size = value
+                    .parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .ok_or_else(|| RulesError::InvalidHandSize(value.to_string()))?;
+            }
+        }
+
+        Ok(Self::new(&order)?
+            .with_wild(&wild)?
+            .with_tie_break(tie_break)
+            .with_hand_size(hand_size))
+    }
+
+    pub fn card(&self, label: char) -> Card {
+        let rank = self
+            .order
+            .iter()
+            .position(|l| *l == label)
+            .expect("invalid card label");
+
+        Card(rank as u8)
+    }
+
+    pub fn label(&self, card: Card) -> char {
+        self.order[card.0 as usize]
+    }
+
+    fn is_wild(&self, card: Card) -> bool {
+        self.wild.contains(&self.label(card))
+    }
+}
+
+/// Hands are compared by kind, then by the tie-break cards and finally card
+/// by card, which is exactly the field order, so `Ord` can be derived.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hand {
+    kind: HandKind,
+    /// Empty for [`TieBreak::Positional`], as `cards` already does the job.
+    tie_break: Vec<Card>,
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn parse(cards: &str, rules: &Rules) -> Self {
+        let cards: Vec<Card> = cards.chars().map(|c| rules.card(c)).collect();
+        assert_eq!(cards.len(), rules.hand_size, "wrong number of cards");
+
+        let mut freq: HashMap<Card, usize> = HashMap::new();
+        let mut wilds = 0;
+
+        for card in cards.iter() {
+            if rules.is_wild(*card) {
+                wilds += 1;
+            } else {
+                *freq.entry(*card).or_insert(0) += 1;
+            }
+        }
+
+        let mut counts: Vec<usize> = freq.values().copied().collect();
+        counts.sort_by(|a, b| b.cmp(a));
+
+        // Wild cards always do best by joining the biggest group.
+        let biggest = counts.first().copied().unwrap_or(0) + wilds;
+        let second_biggest = counts.get(1).copied().unwrap_or(0);
+
+        let tie_break = match rules.tie_break {
+            TieBreak::Positional => Vec::new(),
+            TieBreak::BestCard => {
+                let mut sorted = cards.clone();
+                sorted.sort_by(|a, b| b.cmp(a));
+                sorted
+            }
+        };
+
+        Self {
+            kind: HandKind::from_group_sizes(biggest, second_biggest),
+            tie_break,
+            cards,
+        }
+    }
+
+    pub fn kind(&self) -> HandKind {
+        self.kind
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The cards as written in the puzzle.
+    pub fn labels(&self, rules: &Rules) -> String {
+        self.cards.iter().map(|c| rules.label(*c)).collect()
+    }
+
+    /// The cards with every wild card replaced by the card it stands for,
+    /// which is the most repeated card (the strongest one on ties).
+    pub fn resolved_labels(&self, rules: &Rules) -> String {
+        let mut freq: HashMap<Card, usize> = HashMap::new();
+
+        for card in self.cards.iter().filter(|c| !rules.is_wild(**c)) {
+            *freq.entry(*card).or_insert(0) += 1;
+        }
+
+        let replacement = freq
+            .into_iter()
+            .map(|(card, count)| (count, card))
+            .max()
+            .map(|(_, card)| card)
+            .or_else(|| {
+                // All wild, so go for the strongest card there is.
+                (0..rules.order.len() as u8)
+                    .map(Card)
+                    .filter(|c| !rules.is_wild(*c))
+                    .max()
+            });
+
+        self.cards
+            .iter()
+            .map(|c| match replacement {
+                Some(r) if rules.is_wild(*c) => rules.label(r),
+                _ => rules.label(*c),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Play {
+    pub hand: Hand,
+    pub bid: i64,
+}
+
+impl Play {
+    pub fn parse(s: &str, rules: &Rules) -> Self {
+        let (cards, bid) = s.split_once(' ').expect("could not split hand in two");
+
+        let hand = Hand::parse(cards, rules);
+        let bid: i64 = bid.parse().expect("bid is not a number");
+
+        Self { hand, bid }
+    }
+}
+
+/// All the plays in the puzzle, from the weakest to the strongest.
+fn rank(puzzle: &str, rules: &Rules) -> Vec<Play> {
+    let mut plays: Vec<_> = puzzle
+        .trim()
+        .lines()
+        .map(|l| Play::parse(l, rules))
+        .collect();
+    plays.sort_by(|p1, p2| p1.hand.cmp(&p2.hand));
+    plays
+}
+
+/// Plays sharing the exact same hand. They are ranked in the order they were
+/// dealt, like the stable sort in [`rank`] does.
+#[derive(Debug, Clone, Copy, Default)]
+struct Ties {
+    count: i64,
+    bids: i64,
+    /// Sum of every bid times how many ties came before it.
+    offset_bids: i64,
+}
+
+/// Total winnings of all the plays in the puzzle, read one line at a time.
+///
+/// Plays are grouped by hand instead of being kept around, so memory is
+/// bounded by how many different hands the rules allow rather than by the
+/// length of the puzzle.
+pub fn solve(puzzle: impl BufRead, rules: &Rules) -> io::Result<i64> {
+    let mut hands: BTreeMap<Hand, Ties> = BTreeMap::new();
+
+    reader::for_each_line(puzzle, |line| {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+
+        let play = Play::parse(line, rules);
+        let ties = hands.entry(play.hand).or_default();
+        ties.offset_bids += ties.count * play.bid;
+        ties.count += 1;
+        ties.bids += play.bid;
+    })?;
+
+    let mut rank = 1;
+    Ok(hands
+        .values()
+        .map(|ties| {
+            let winnings = rank * ties.bids + ties.offset_bids;
+            rank += ties.count;
+            winnings
+        })
+        .sum())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainFormat {
+    Table,
+    Csv,
+}
+
+impl ExplainFormat {
+    /// Looks for `--explain` (a table) or `--explain=csv` in the arguments.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        args.find_map(|arg| match arg.as_str() {
+            "--explain" | "--explain=table" => Some(Self::Table),
+            "--explain=csv" => Some(Self::Csv),
+            _ => None,
+        })
+    }
+}
+
+/// How a single play was ranked by [`solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub cards: String,
+    pub resolved: String,
+    pub kind: HandKind,
+    pub rank: i64,
+    pub bid: i64,
+    pub winnings: i64,
+}
+
+/// Same as [`solve`] but returns every play instead of the total, from the
+/// weakest to the strongest.
+pub fn explain(puzzle: &str, rules: &Rules) -> Vec<Explanation> {
+    rank(puzzle, rules)
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let rank = (i + 1) as i64;
+
+            Explanation {
+                cards: p.hand.labels(rules),
+                resolved: p.hand.resolved_labels(rules),
+                kind: p.hand.kind(),
+                rank,
+                bid: p.bid,
+                winnings: rank * p.bid,
+            }
+        })
+        .collect()
+}
+
+pub fn render_explanation(explanation: &[Explanation], format: ExplainFormat) -> String {
+    let header = ["rank", "cards", "resolved", "kind", "bid", "winnings"];
+
+    let rows: Vec<[String; 6]> = explanation
+        .iter()
+        .map(|e| {
+            [
+                e.rank.to_string(),
+                e.cards.clone(),
+                e.resolved.clone(),
+                format!("{:?}", e.kind),
+                e.bid.to_string(),
+                e.winnings.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut output = String::new();
+
+    match format {
+        ExplainFormat::Csv => {
+            output.push_str(&header.join(","));
+            output.push('\n');
+
+            for row in rows.iter() {
+                output.push_str(&row.join(","));
+                output.push('\n');
+            }
+        }
+        ExplainFormat::Table => {
+            let widths: Vec<usize> = (0..header.len())
+                .map(|i| {
+                    rows.iter()
+                        .map(|r| r[i].len())
+                        .chain([header[i].len()])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+
+            let header = header.map(String::from);
+
+            for row in [&header].into_iter().chain(rows.iter()) {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+
+                output.push_str(cells.join("  ").trim_end());
+                output.push('\n');
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    /// Compares two hands as written in the puzzle statement, without going
+    /// through [`Hand`].
+    fn reference_cmp(a: &str, b: &str, rules: &Rules) -> Ordering {
+        fn plain_kind(cards: &[char]) -> HandKind {
+            let mut counts: Vec<usize> = cards
+                .iter()
+                .map(|c| cards.iter().filter(|o| *o == c).count())
+                .collect();
+            counts.sort();
+
+            match counts.as_slice() {
+                [5, 5, 5, 5, 5] => HandKind::FiveOfAKind,
+                [1, 4, 4, 4, 4] => HandKind::FourOfAKind,
+                [2, 2, 3, 3, 3] => HandKind::FullHouse,
+                [1, 1, 3, 3, 3] => HandKind::ThreeOfAKind,
+                [1, 2, 2, 2, 2] => HandKind::TwoPair,
+                [1, 1, 1, 2, 2] => HandKind::OnePair,
+                _ => HandKind::HighCard,
+            }
+        }
+
+        // Tries every possible replacement for each joker.
+        fn best_kind(cards: Vec<char>, rules: &Rules) -> HandKind {
+            match cards.iter().position(|c| rules.wild.contains(c)) {
+                None => plain_kind(&cards),
+                Some(i) => rules
+                    .order
+                    .iter()
+                    .copied()
+                    .filter(|c| !rules.wild.contains(c))
+                    .map(|replacement| {
+                        let mut cards = cards.clone();
+                        cards[i] = replacement;
+                        best_kind(cards, rules)
+                    })
+                    .max()
+                    .unwrap(),
+            }
+        }
+
+        let strength = |c: char| rules.card(c);
+
+        best_kind(a.chars().collect(), rules)
+            .cmp(&best_kind(b.chars().collect(), rules))
+            .then_with(|| {
+                a.chars()
+                    .zip(b.chars())
+                    .map(|(x, y)| strength(x).cmp(&strength(y)))
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+
+    /// Deterministic pseudo random hands, so failures are reproducible.
+    fn random_hands(seed: u64, count: usize) -> Vec<String> {
+        let labels: Vec<char> = "23456789TJQKA".chars().collect();
+        let mut state = seed;
+
+        (0..count)
+            .map(|_| {
+                (0..5)
+                    .map(|_| {
+                        // xorshift64
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        // Bias towards few labels so that every kind shows up.
+                        labels[(state % 5) as usize * 3 % labels.len()]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    const EXAMPLE_PUZZLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    #[test]
+    fn test_resolved_labels() {
+        let rules = Rules::jokers();
+        let resolved = |cards| Hand::parse(cards, &rules).resolved_labels(&rules);
+
+        assert_eq!(resolved("KTJJT"), "KTTTT");
+        assert_eq!(resolved("JJJJJ"), "AAAAA");
+        assert_eq!(resolved("2J3J4"), "24344");
+        assert_eq!(resolved("23456"), "23456");
+    }
+
+    #[test]
+    fn test_explain_matches_solve() {
+        let rules = Rules::jokers();
+        let explanation = explain(EXAMPLE_PUZZLE, &rules);
+
+        let total: i64 = explanation.iter().map(|e| e.winnings).sum();
+        assert_eq!(total, solve(EXAMPLE_PUZZLE.as_bytes(), &rules).unwrap());
+
+        let strongest = explanation.last().unwrap();
+        assert_eq!(strongest.cards, "KTJJT");
+        assert_eq!(strongest.resolved, "KTTTT");
+        assert_eq!(strongest.kind, HandKind::FourOfAKind);
+        assert_eq!(strongest.rank, 5);
+        assert_eq!(strongest.winnings, 1100);
+    }
+
+    #[test]
+    fn test_render_explanation() {
+        let explanation = explain("KK677 28\n32T3K 765\n", &Rules::standard());
+
+        assert_eq!(
+            render_explanation(&explanation, ExplainFormat::Csv),
+            "rank,cards,resolved,kind,bid,winnings
+1,32T3K,32T3K,OnePair,765,765
+2,KK677,KK677,TwoPair,28,56
+"
+        );
+
+        assert_eq!(
+            render_explanation(&explanation, ExplainFormat::Table),
+            "rank  cards  resolved  kind     bid  winnings
+1     32T3K  32T3K     OnePair  765  765
+2     KK677  KK677     TwoPair  28   56
+"
+        );
+    }
+
+    #[test]
+    fn test_explain_format_from_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(ExplainFormat::from_args(args(&["d7p1"]).into_iter()), None);
+        assert_eq!(
+            ExplainFormat::from_args(args(&["d7p1", "--explain"]).into_iter()),
+            Some(ExplainFormat::Table)
+        );
+        assert_eq!(
+            ExplainFormat::from_args(args(&["d7p1", "--explain=csv"]).into_iter()),
+            Some(ExplainFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn test_kind_ordering() {
+        assert!(HandKind::FiveOfAKind > HandKind::FourOfAKind);
+        assert!(HandKind::FourOfAKind > HandKind::FullHouse);
+        assert!(HandKind::FullHouse > HandKind::ThreeOfAKind);
+        assert!(HandKind::ThreeOfAKind > HandKind::TwoPair);
+        assert!(HandKind::TwoPair > HandKind::OnePair);
+        assert!(HandKind::OnePair > HandKind::HighCard);
+    }
+
+    #[test]
+    fn test_card_ranks() {
+        let standard = Rules::standard();
+        assert!(standard.card('J') > standard.card('T'));
+        assert!(standard.card('A') > standard.card('K'));
+
+        let jokers = Rules::jokers();
+        assert!(jokers.card('J') < jokers.card('2'));
+        assert_eq!(jokers.label(jokers.card('Q')), 'Q');
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert_eq!(Rules::new("23A3"), Err(RulesError::DuplicatedLabel('3')));
+
+        let labels: String = (0..300).filter_map(|i| char::from_u32(0x100 + i)).collect();
+        let error = Rules::new(&labels).unwrap_err();
+        assert_eq!(error, RulesError::TooManyLabels(300));
+        assert_eq!(
+            error.to_string(),
+            "300 card labels, but there can't be more than 255"
+        );
+
+        let error = Rules::standard().with_wild("J1").unwrap_err();
+        assert_eq!(error, RulesError::WildNotACard('1'));
+        assert_eq!(error.to_string(), "wild card '1' is not in the card order");
+    }
+
+    #[test]
+    fn test_rules_from_args() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            Rules::from_args(args(&["d7p2"]), &Rules::jokers()),
+            Ok(Rules::jokers())
+        );
+        assert_eq!(
+            Rules::from_args(
+                args(&[
+                    "d7p1",
+                    "--cards=J23456789TQKA",
+                    "--wild=J2",
+                    "--tie-break=best-card",
+                    "--hand-size=6"
+                ]),
+                &Rules::standard()
+            ),
+            Ok(Rules::jokers()
+                .with_wild("J2")
+                .unwrap()
+                .with_tie_break(TieBreak::BestCard)
+                .with_hand_size(6))
+        );
+        assert_eq!(
+            Rules::from_args(args(&["d7p2", "--wild="]), &Rules::jokers()),
+            Rules::new("J23456789TQKA")
+        );
+
+        // The default wild card must still be a card.
+        assert_eq!(
+            Rules::from_args(args(&["d7p2", "--cards=23456789TQKA"]), &Rules::jokers()),
+            Err(RulesError::WildNotACard('J'))
+        );
+        assert_eq!(
+            Rules::from_args(args(&["d7p1", "--tie-break=best"]), &Rules::standard()),
+            Err(RulesError::UnknownTieBreak("best".to_string()))
+        );
+        assert_eq!(
+            Rules::from_args(args(&["d7p1", "--hand-size=0"]), &Rules::standard()),
+            Err(RulesError::InvalidHandSize("0".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_multiple_wild_cards() {
+        let rules = Rules::jokers().with_wild("J2").unwrap();
+
+        assert_eq!(Hand::parse("J2KK3", &rules).kind(), HandKind::FourOfAKind);
+        assert_eq!(Hand::parse("22JJ3", &rules).kind(), HandKind::FiveOfAKind);
+        assert_eq!(Hand::parse("2J345", &rules).kind(), HandKind::ThreeOfAKind);
+    }
+
+    #[test]
+    fn test_six_card_hands() {
+        let rules = Rules::standard().with_hand_size(6);
+
+        assert_eq!(Hand::parse("AAAAAA", &rules).kind(), HandKind::SixOfAKind);
+        assert_eq!(Hand::parse("AAAAAK", &rules).kind(), HandKind::FiveOfAKind);
+        assert_eq!(Hand::parse("AAAKKK", &rules).kind(), HandKind::FullHouse);
+        assert_eq!(Hand::parse("AAKKQQ", &rules).kind(), HandKind::TwoPair);
+
+        let puzzle = "AAAAAK 1\nKKKKKK 10\n";
+        assert_eq!(solve(puzzle.as_bytes(), &rules).unwrap(), 21);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong number of cards")]
+    fn test_hand_size_is_checked() {
+        Hand::parse("AAAAAA", &Rules::standard());
+    }
+
+    #[test]
+    fn test_best_card_tie_break() {
+        let positional = Rules::standard();
+        let best_card = Rules::standard().with_tie_break(TieBreak::BestCard);
+
+        // Same kind, positional compares 2 vs 3 but best card compares A vs K.
+        assert!(Hand::parse("2A345", &positional) < Hand::parse("3K456", &positional));
+        assert!(Hand::parse("2A345", &best_card) > Hand::parse("3K456", &best_card));
+
+        // Same cards in a different order are only equal in strength.
+        let a = Hand::parse("A2345", &best_card);
+        let b = Hand::parse("5432A", &best_card);
+        assert_ne!(a, b);
+        assert_eq!(a.cmp(&b), a.cards().cmp(b.cards()));
+    }
+
+    #[test]
+    fn test_ordering_is_total() {
+        let variant = Rules::jokers()
+            .with_wild("J2")
+            .unwrap()
+            .with_tie_break(TieBreak::BestCard);
+
+        for rules in [Rules::standard(), Rules::jokers(), variant] {
+            let hands: Vec<_> = random_hands(7, 60)
+                .iter()
+                .map(|h| Hand::parse(h, &rules))
+                .collect();
+
+            for a in hands.iter() {
+                for b in hands.iter() {
+                    assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                    assert_eq!(a.cmp(b).is_eq(), a == b);
+
+                    for c in hands.iter() {
+                        if a <= b && b <= c {
+                            assert!(a <= c, "{:?} <= {:?} <= {:?}", a, b, c);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_ranks_ties_in_order() {
+        // Few labels, so many plays share the exact same hand.
+        let puzzle: String = random_hands(3, 500)
+            .iter()
+            .zip(1..)
+            .map(|(hand, bid)| format!("{} {}\n", hand, bid * 7 % 100))
+            .collect();
+
+        for rules in [Rules::standard(), Rules::jokers()] {
+            let total: i64 = explain(&puzzle, &rules).iter().map(|e| e.winnings).sum();
+            assert_eq!(solve(puzzle.as_bytes(), &rules).unwrap(), total);
+        }
+    }
+
+    #[test]
+    fn test_ordering_matches_puzzle_rules() {
+        let variant = Rules::jokers().with_wild("J2").unwrap();
+
+        for rules in [Rules::standard(), Rules::jokers(), variant] {
+            let hands = random_hands(42, 400);
+
+            for pair in hands.windows(2) {
+                let (a, b) = (&pair[0], &pair[1]);
+                let got = Hand::parse(a, &rules).cmp(&Hand::parse(b, &rules));
+                assert_eq!(got, reference_cmp(a, b, &rules), "{} vs {}", a, b);
+            }
+        }
+    }
+}
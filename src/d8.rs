@@ -0,0 +1,1037 @@
+//! Haunted Wasteland (day 8).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::math::{gcd, lcm};
+use crate::parse::{self, ParseError, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    Syntax(ParseError),
+    InvalidDirection(char),
+    DuplicatedNode(String),
+    UnknownNode(String),
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax(e) => write!(f, "{}", e),
+            Self::InvalidDirection(c) => write!(f, "invalid direction {:?}", c),
+            Self::DuplicatedNode(name) => write!(f, "node {} is defined twice", name),
+            Self::UnknownNode(name) => write!(f, "node {} is referenced but not defined", name),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+impl From<ParseError> for NetworkError {
+    fn from(e: ParseError) -> Self {
+        Self::Syntax(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalkError {
+    /// The walk went back to a node it already left from at the start of
+    /// the directions without finding a goal, so it would go on forever.
+    Unreachable,
+    StepLimit(u64),
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "no goal node can be reached"),
+            Self::StepLimit(limit) => write!(f, "no goal node reached in {} steps", limit),
+        }
+    }
+}
+
+impl std::error::Error for WalkError {}
+
+/// Matches node names. Parsed from a glob where `?` stands for any character
+/// and `*` for any run of characters, so `AAA` is an exact name, `*Z` a
+/// suffix and `A*` a prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodePattern {
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Glob(String),
+}
+
+impl NodePattern {
+    pub fn parse(s: &str) -> Self {
+        let is_plain = |s: &str| !s.contains(['*', '?']);
+
+        if is_plain(s) {
+            Self::Exact(s.to_string())
+        } else if let Some(prefix) = s.strip_suffix('*').filter(|p| is_plain(p)) {
+            Self::Prefix(prefix.to_string())
+        } else if let Some(suffix) = s.strip_prefix('*').filter(|p| is_plain(p)) {
+            Self::Suffix(suffix.to_string())
+        } else {
+            Self::Glob(s.to_string())
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        fn glob(pattern: &[char], name: &[char]) -> bool {
+            match (pattern.first(), name.first()) {
+                (None, None) => true,
+                (Some('*'), _) => {
+                    glob(&pattern[1..], name) || (!name.is_empty() && glob(pattern, &name[1..]))
+                }
+                (Some('?'), Some(_)) => glob(&pattern[1..], &name[1..]),
+                (Some(p), Some(n)) if p == n => glob(&pattern[1..], &name[1..]),
+                _ => false,
+            }
+        }
+
+        match self {
+            Self::Exact(exact) => name == exact,
+            Self::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            Self::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            Self::Glob(pattern) => {
+                let pattern: Vec<char> = pattern.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                glob(&pattern, &name)
+            }
+        }
+    }
+}
+
+/// The network with node names interned into dense indices, so walking it
+/// is just array lookups.
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, u32>,
+    /// `true` means right.
+    directions: Vec<bool>,
+    left: Vec<u32>,
+    right: Vec<u32>,
+    /// Where each node ends up after following all the directions once.
+    jump: Vec<u32>,
+}
+
+impl<'a> Network<'a> {
+    pub fn parse(puzzle: &'a str) -> Result<Self, NetworkError> {
+        let puzzle = Span::new(puzzle);
+        let mut lines = puzzle
+            .lines()
+            .filter(|line| !line.as_str().trim().is_empty());
+
+        let directions = lines
+            .next()
+            .ok_or_else(|| puzzle.error("no directions to read"))?
+            .as_str()
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'L' => Ok(false),
+                'R' => Ok(true),
+                _ => Err(NetworkError::InvalidDirection(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // `AAA = (BBB, CCC)`
+        let nodes: Vec<(&str, &str, &str)> = lines
+            .map(|line| {
+                let (from, to) = line.split_once("=")?;
+
+                match parse::tuple(to)?[..] {
+                    [left, right] => Ok((from.trim().as_str(), left.as_str(), right.as_str())),
+                    _ => Err(to.trim().error("expected two nodes")),
+                }
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        let mut ids = HashMap::new();
+
+        for (i, (name, _, _)) in nodes.iter().enumerate() {
+            if ids.insert(*name, i as u32).is_some() {
+                return Err(NetworkError::DuplicatedNode(name.to_string()));
+            }
+        }
+
+        let resolve = |name: &str| {
+            ids.get(name)
+                .copied()
+                .ok_or_else(|| NetworkError::UnknownNode(name.to_string()))
+        };
+
+        let left = nodes
+            .iter()
+            .map(|(_, l, _)| resolve(l))
+            .collect::<Result<Vec<_>, _>>()?;
+        let right = nodes
+            .iter()
+            .map(|(_, _, r)| resolve(r))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut network = Self {
+            names: nodes.iter().map(|(name, _, _)| *name).collect(),
+            ids,
+            directions,
+            left,
+            right,
+            jump: Vec::new(),
+        };
+
+        network.jump = network
+            .nodes()
+            .map(|node| {
+                (0..network.directions.len()).fold(node, |node, index| network.step(node, index))
+            })
+            .collect();
+
+        Ok(network)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn directions_len(&self) -> usize {
+        self.directions.len()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = u32> {
+        0..self.names.len() as u32
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, node: u32) -> &'a str {
+        self.names[node as usize]
+    }
+
+    /// Nodes whose name matches the pattern, in input order.
+    pub fn find_all<'p>(&'p self, pattern: &'p NodePattern) -> impl Iterator<Item = u32> + 'p {
+        self.nodes()
+            .filter(|node| pattern.matches(self.name(*node)))
+    }
+
+    /// Next node when following the direction at `index`.
+    pub fn step(&self, node: u32, index: usize) -> u32 {
+        if self.directions[index % self.directions.len()] {
+            self.right[node as usize]
+        } else {
+            self.left[node as usize]
+        }
+    }
+
+    /// Node reached after following all the directions once.
+    pub fn jump(&self, node: u32) -> u32 {
+        self.jump[node as usize]
+    }
+
+    /// Steps needed to go from every node matching `is_start` to a goal
+    /// node, giving up after `max_steps`, in input order.
+    ///
+    /// Whole passes over the directions are skipped with [`Network::jump`]
+    /// unless a goal is hit in the middle of them.
+    pub fn walk(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_goal: impl Fn(&str) -> bool,
+        max_steps: u64,
+    ) -> Vec<(u32, Result<u64, WalkError>)> {
+        let goal_offsets = self.goal_offsets(is_goal);
+
+        self.nodes()
+            .filter(|node| is_start(self.name(*node)))
+            .map(|start| (start, self.walk_from(start, &goal_offsets, max_steps)))
+            .collect()
+    }
+
+    fn walk_from(
+        &self,
+        start: u32,
+        goal_offsets: &[Option<usize>],
+        max_steps: u64,
+    ) -> Result<u64, WalkError> {
+        let mut pass_starts = vec![false; self.len()];
+
+        let mut node = start;
+        let mut steps: u64 = 0;
+
+        loop {
+            if let Some(offset) = goal_offsets[node as usize] {
+                let steps = steps + offset as u64;
+
+                return if steps > max_steps {
+                    Err(WalkError::StepLimit(max_steps))
+                } else {
+                    Ok(steps)
+                };
+            }
+
+            if pass_starts[node as usize] {
+                return Err(WalkError::Unreachable);
+            }
+
+            pass_starts[node as usize] = true;
+            node = self.jump(node);
+            steps += self.directions.len() as u64;
+
+            if steps > max_steps {
+                return Err(WalkError::StepLimit(max_steps));
+            }
+        }
+    }
+
+    /// For every node, the first step of a pass over the directions at which
+    /// a goal is reached, if any.
+    fn goal_offsets(&self, is_goal: impl Fn(&str) -> bool) -> Vec<Option<usize>> {
+        let goals: Vec<bool> = self.names.iter().map(|name| is_goal(name)).collect();
+
+        self.nodes()
+            .map(|start| {
+                let mut node = start;
+
+                (0..self.directions.len()).find(|index| {
+                    let found = goals[node as usize];
+                    node = self.step(node, *index);
+                    found
+                })
+            })
+            .collect()
+    }
+
+    /// Nodes visited when walking from `start`, including it, until a goal
+    /// is reached or the walk starts repeating itself.
+    pub fn trace(&self, start: u32, is_goal: impl Fn(&str) -> bool) -> Vec<u32> {
+        let directions_len = self.directions.len();
+        let mut seen = vec![false; self.len() * directions_len];
+
+        let mut path = vec![start];
+        let mut node = start;
+        let mut steps = 0;
+
+        while !is_goal(self.name(node)) {
+            let index = steps % directions_len;
+            let state = node as usize * directions_len + index;
+
+            if seen[state] {
+                break;
+            }
+
+            seen[state] = true;
+            node = self.step(node, index);
+            path.push(node);
+            steps += 1;
+        }
+
+        path
+    }
+
+    /// Which nodes can be reached from `start` when any direction can be
+    /// taken at any step.
+    fn reachable_from(&self, start: u32) -> Vec<bool> {
+        let mut reachable = vec![false; self.len()];
+        let mut pending = vec![start];
+
+        while let Some(node) = pending.pop() {
+            if reachable[node as usize] {
+                continue;
+            }
+
+            reachable[node as usize] = true;
+            pending.push(self.left[node as usize]);
+            pending.push(self.right[node as usize]);
+        }
+
+        reachable
+    }
+
+    /// Renders the network as a Graphviz DOT digraph. Start and end nodes
+    /// are filled and the edges taken by `paths` (as returned by
+    /// [`Network::trace`]) are highlighted with the steps they were taken at.
+    pub fn to_dot(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_end: impl Fn(&str) -> bool,
+        paths: &[Vec<u32>],
+    ) -> String {
+        let mut walked: HashMap<(u32, bool), Vec<usize>> = HashMap::new();
+
+        for path in paths {
+            for (i, pair) in path.windows(2).enumerate() {
+                let dir = self.directions[i % self.directions.len()];
+                walked.entry((pair[0], dir)).or_default().push(i);
+            }
+        }
+
+        let edge = |from: u32, to: u32, label: &str, steps: Vec<usize>| {
+            let mut attrs = format!("label=\"{}", label);
+
+            if !steps.is_empty() {
+                let shown: Vec<String> = steps.iter().take(3).map(|s| s.to_string()).collect();
+                let more = if steps.len() > 3 { ", ..." } else { "" };
+                attrs.push_str(&format!(
+                    " @ {}{}\", color=red, penwidth=2",
+                    shown.join(", "),
+                    more
+                ));
+            } else {
+                attrs.push('"');
+            }
+
+            format!(
+                "    \"{}\" -> \"{}\" [{}];\n",
+                self.name(from),
+                self.name(to),
+                attrs
+            )
+        };
+
+        let mut dot = String::from("digraph network {\n");
+
+        for node in self.nodes() {
+            let name = self.name(node);
+
+            if is_start(name) {
+                dot.push_str(&format!(
+                    "    \"{}\" [style=filled, fillcolor=palegreen];\n",
+                    name
+                ));
+            } else if is_end(name) {
+                dot.push_str(&format!(
+                    "    \"{}\" [style=filled, fillcolor=lightcoral];\n",
+                    name
+                ));
+            }
+        }
+
+        for node in self.nodes() {
+            let left = self.left[node as usize];
+            let right = self.right[node as usize];
+            let left_steps = walked.remove(&(node, false)).unwrap_or_default();
+            let right_steps = walked.remove(&(node, true)).unwrap_or_default();
+
+            if left == right {
+                let mut steps = [left_steps, right_steps].concat();
+                steps.sort();
+                steps.dedup();
+                dot.push_str(&edge(node, left, "L/R", steps));
+            } else {
+                dot.push_str(&edge(node, left, "L", left_steps));
+                dot.push_str(&edge(node, right, "R", right_steps));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Which end nodes each start node can get to, and which nodes can't get
+    /// to any end node at all.
+    pub fn reachability(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_end: impl Fn(&str) -> bool,
+    ) -> Reachability<'a> {
+        let ends: Vec<u32> = self.nodes().filter(|n| is_end(self.name(*n))).collect();
+
+        let starts = self
+            .nodes()
+            .filter(|n| is_start(self.name(*n)))
+            .map(|start| {
+                let reachable = self.reachable_from(start);
+                let walked = self.trace(start, |_| false);
+
+                StartReachability {
+                    start: self.name(start),
+                    reachable_ends: ends
+                        .iter()
+                        .filter(|e| reachable[**e as usize])
+                        .map(|e| self.name(*e))
+                        .collect(),
+                    walked_ends: ends
+                        .iter()
+                        .filter(|e| walked.contains(e))
+                        .map(|e| self.name(*e))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let reaches_end = self.reaching(&ends);
+        let dead_ends = self
+            .nodes()
+            .filter(|n| !reaches_end[*n as usize])
+            .map(|n| self.name(n))
+            .collect();
+
+        Reachability { starts, dead_ends }
+    }
+
+    /// Which nodes can reach any of `targets`, including them, when any
+    /// direction can be taken at any step. A single search backwards from
+    /// all the targets at once.
+    fn reaching(&self, targets: &[u32]) -> Vec<bool> {
+        let mut parents: Vec<Vec<u32>> = vec![Vec::new(); self.len()];
+
+        for node in self.nodes() {
+            parents[self.left[node as usize] as usize].push(node);
+            parents[self.right[node as usize] as usize].push(node);
+        }
+
+        let mut reaching = vec![false; self.len()];
+        let mut pending = targets.to_vec();
+
+        while let Some(node) = pending.pop() {
+            if reaching[node as usize] {
+                continue;
+            }
+
+            reaching[node as usize] = true;
+            pending.extend(&parents[node as usize]);
+        }
+
+        reaching
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartReachability<'a> {
+    pub start: &'a str,
+    /// End nodes reachable taking any direction at any step.
+    pub reachable_ends: Vec<&'a str>,
+    /// End nodes actually visited when following the directions.
+    pub walked_ends: Vec<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reachability<'a> {
+    pub starts: Vec<StartReachability<'a>>,
+    /// Nodes from which no end node can be reached.
+    pub dead_ends: Vec<&'a str>,
+}
+
+impl fmt::Display for Reachability<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for start in self.starts.iter() {
+            writeln!(
+                f,
+                "{}: reachable [{}], walked [{}]",
+                start.start,
+                start.reachable_ends.join(", "),
+                start.walked_ends.join(", ")
+            )?;
+        }
+
+        writeln!(f, "dead ends: [{}]", self.dead_ends.join(", "))
+    }
+}
+
+/// Solves `x = a1 (mod m1)` and `x = a2 (mod m2)` for moduli that don't need
+/// to be coprime. Returns the solution modulo the lcm of both moduli.
+fn crt((a1, m1): (u128, u128), (a2, m2): (u128, u128)) -> Option<(u128, u128)> {
+    fn inverse(a: i128, m: i128) -> i128 {
+        // Extended Euclid, only called with coprime arguments.
+        let (mut old_r, mut r) = (a, m);
+        let (mut old_s, mut s) = (1, 0);
+
+        while r != 0 {
+            let q = old_r / r;
+            (old_r, r) = (r, old_r - q * r);
+            (old_s, s) = (s, old_s - q * s);
+        }
+
+        old_s.rem_euclid(m)
+    }
+
+    let g = gcd(m1, m2);
+    let diff = a2 as i128 - a1 as i128;
+
+    if diff % g as i128 != 0 {
+        return None;
+    }
+
+    let m2g = (m2 / g) as i128;
+    let t = (diff / g as i128).rem_euclid(m2g) * inverse((m1 / g) as i128 % m2g, m2g) % m2g;
+    let modulus = lcm(m1, m2);
+
+    Some(((a1 + m1 * t as u128) % modulus, modulus))
+}
+
+/// The path of a single ghost, which always ends up looping because there
+/// are finitely many `(node, instruction index)` states.
+///
+/// Steps at which the ghost is on an end node are either in `tail_hits`,
+/// which only happen once, or in `cycle_hits`, which repeat every `length`
+/// steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: u64,
+    pub length: u64,
+    pub tail_hits: Vec<u64>,
+    pub cycle_hits: Vec<u64>,
+}
+
+impl Cycle {
+    pub fn find(network: &Network, start: u32, is_end: impl Fn(&str) -> bool) -> Self {
+        let directions_len = network.directions_len();
+        let mut seen: Vec<Option<u64>> = vec![None; network.len() * directions_len];
+        let mut hits = Vec::new();
+
+        let mut node = start;
+        let mut steps: u64 = 0;
+
+        loop {
+            let index = steps as usize % directions_len;
+            let state = node as usize * directions_len + index;
+
+            if let Some(tail) = seen[state] {
+                let (tail_hits, cycle_hits) = hits.iter().partition(|h| **h < tail);
+
+                return Self {
+                    tail,
+                    length: steps - tail,
+                    tail_hits,
+                    cycle_hits,
+                };
+            }
+
+            seen[state] = Some(steps);
+
+            if is_end(network.name(node)) {
+                hits.push(steps);
+            }
+
+            node = network.step(node, index);
+            steps += 1;
+        }
+    }
+
+    pub fn hits_at(&self, steps: u64) -> bool {
+        if steps < self.tail {
+            return self.tail_hits.contains(&steps);
+        }
+
+        let offset = (steps - self.tail) % self.length;
+        self.cycle_hits.iter().any(|h| h - self.tail == offset)
+    }
+
+    pub fn first_hit(&self) -> Option<u64> {
+        self.tail_hits.first().or(self.cycle_hits.first()).copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub cycles: Vec<Cycle>,
+    /// The first step at which every ghost is on an end node, if any.
+    pub earliest: Option<u128>,
+    /// Whether taking the LCM of the first hits would have given `earliest`.
+    pub lcm_shortcut_valid: bool,
+}
+
+impl Analysis {
+    pub fn new(cycles: Vec<Cycle>) -> Self {
+        let earliest = earliest_common_hit(&cycles);
+        let lcm_of_first_hits = cycles
+            .iter()
+            .try_fold(1, |acc, c| Some(lcm(acc, c.first_hit()? as u128)));
+
+        Self {
+            lcm_shortcut_valid: earliest.is_some() && lcm_of_first_hits == earliest,
+            earliest,
+            cycles,
+        }
+    }
+}
+
+fn earliest_common_hit(cycles: &[Cycle]) -> Option<u128> {
+    let longest_tail = cycles.iter().max_by_key(|c| c.tail)?;
+
+    // Before every ghost is looping, a common hit must be one of the tail
+    // hits of the ghost with the longest tail.
+    if let Some(hit) = longest_tail
+        .tail_hits
+        .iter()
+        .find(|h| cycles.iter().all(|c| c.hits_at(**h)))
+    {
+        return Some(*hit as u128);
+    }
+
+    // After that, every ghost hits at some fixed residues modulo its cycle
+    // length, so try every combination of them.
+    let bound = longest_tail.tail as u128;
+    let mut combinations: Vec<(u128, u128)> = vec![(0, 1)];
+
+    for cycle in cycles {
+        let length = cycle.length as u128;
+
+        combinations = combinations
+            .iter()
+            .flat_map(|c| {
+                cycle
+                    .cycle_hits
+                    .iter()
+                    .filter_map(move |h| crt(*c, (*h as u128 % length, length)))
+            })
+            .collect();
+    }
+
+    combinations
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= bound {
+                residue
+            } else {
+                residue + (bound - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG_EXAMPLE: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    // Ghost 1 is on an end node at every odd step and ghost 2 at 2, 5, 8...
+    // so the first hits are 1 and 2 but they only meet at step 5.
+    const UNCLEAN_EXAMPLE: &str = "L
+
+1A = (1Z, 1Z)
+1Z = (1B, 1B)
+1B = (1Z, 1Z)
+2A = (2B, 2B)
+2B = (2Z, 2Z)
+2Z = (2C, 2C)
+2C = (2D, 2D)
+2D = (2Z, 2Z)
+";
+
+    fn analyze(puzzle: &str) -> Analysis {
+        let network = Network::parse(puzzle).unwrap();
+
+        Analysis::new(
+            network
+                .nodes()
+                .filter(|node| network.name(*node).ends_with('A'))
+                .map(|node| Cycle::find(&network, node, |id| id.ends_with('Z')))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let network = Network::parse(LONG_EXAMPLE).unwrap();
+        let node = network.id("11B").unwrap();
+
+        assert_eq!(network.len(), 8);
+        assert_eq!(network.directions_len(), 2);
+        assert_eq!(network.name(network.step(node, 0)), "XXX");
+        assert_eq!(network.name(network.step(node, 1)), "11Z");
+        assert_eq!(network.name(network.jump(node)), "XXX");
+        assert_eq!(
+            network.name(network.jump(network.id("11A").unwrap())),
+            "11Z"
+        );
+    }
+
+    #[test]
+    fn test_parse_validation() {
+        assert_eq!(
+            Network::parse("LR\n\nAAA = (BBB, AAA)\n").unwrap_err(),
+            NetworkError::UnknownNode("BBB".to_string())
+        );
+        assert_eq!(
+            Network::parse("LX\n\nAAA = (AAA, AAA)\n").unwrap_err(),
+            NetworkError::InvalidDirection('X')
+        );
+        assert_eq!(
+            Network::parse("L\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)\n").unwrap_err(),
+            NetworkError::DuplicatedNode("AAA".to_string())
+        );
+
+        let error = Network::parse("L\n\nAAA = (AAA, AAA)\nBBB = (AAA)\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 4, column 7: expected two nodes");
+        assert!(matches!(
+            Network::parse("L\n\nAAA (AAA, AAA)\n"),
+            Err(NetworkError::Syntax(ParseError { line: 3, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_walk() {
+        let network = Network::parse(LONG_EXAMPLE).unwrap();
+        let id = |name| network.id(name).unwrap();
+        let start = NodePattern::parse("22A");
+
+        // The goal is hit in the middle of the second pass.
+        assert_eq!(
+            network.walk(|id| start.matches(id), |id| id.ends_with('Z'), 100),
+            [(id("22A"), Ok(3))]
+        );
+        assert_eq!(
+            network.walk(|id| start.matches(id), |id| id == "22A", 100),
+            [(id("22A"), Ok(0))]
+        );
+
+        let starts = NodePattern::parse("*A");
+        assert_eq!(
+            network.walk(|id| starts.matches(id), |id| id.ends_with('Z'), 100),
+            [(id("11A"), Ok(2)), (id("22A"), Ok(3))]
+        );
+        assert_eq!(network.walk(|id| id == "33A", |_| true, 100), []);
+    }
+
+    #[test]
+    fn test_walk_errors() {
+        let network = Network::parse(LONG_EXAMPLE).unwrap();
+        let id = |name| network.id(name).unwrap();
+
+        assert_eq!(
+            network.walk(|id| id.ends_with('A'), |id| id == "11Z", 100),
+            [(id("11A"), Ok(2)), (id("22A"), Err(WalkError::Unreachable))]
+        );
+        assert_eq!(
+            network.walk(|id| id.ends_with('A'), |id| id.ends_with('Z'), 2),
+            [
+                (id("11A"), Ok(2)),
+                (id("22A"), Err(WalkError::StepLimit(2)))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_node_pattern() {
+        assert_eq!(
+            NodePattern::parse("AAA"),
+            NodePattern::Exact("AAA".to_string())
+        );
+        assert_eq!(
+            NodePattern::parse("*Z"),
+            NodePattern::Suffix("Z".to_string())
+        );
+        assert_eq!(
+            NodePattern::parse("1*"),
+            NodePattern::Prefix("1".to_string())
+        );
+        assert_eq!(
+            NodePattern::parse("?2*"),
+            NodePattern::Glob("?2*".to_string())
+        );
+
+        assert!(NodePattern::parse("*Z").matches("11Z"));
+        assert!(!NodePattern::parse("*Z").matches("11A"));
+        assert!(NodePattern::parse("?2*").matches("22A"));
+        assert!(!NodePattern::parse("?2*").matches("11A"));
+        assert!(NodePattern::parse("1*Z").matches("1Z"));
+
+        let network = Network::parse(LONG_EXAMPLE).unwrap();
+        let pattern = NodePattern::parse("*A");
+        let starts: Vec<_> = network
+            .find_all(&pattern)
+            .map(|n| network.name(n))
+            .collect();
+        assert_eq!(starts, vec!["11A", "22A"]);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let analysis = analyze(LONG_EXAMPLE);
+
+        assert_eq!(
+            analysis.cycles[0],
+            Cycle {
+                tail: 1,
+                length: 2,
+                tail_hits: vec![],
+                cycle_hits: vec![2],
+            }
+        );
+
+        assert_eq!(
+            analysis.cycles[1],
+            Cycle {
+                tail: 1,
+                length: 6,
+                tail_hits: vec![],
+                cycle_hits: vec![3, 6],
+            }
+        );
+    }
+
+    #[test]
+    fn test_clean_cycles() {
+        let analysis = analyze(LONG_EXAMPLE);
+        assert_eq!(analysis.earliest, Some(6));
+        assert!(analysis.lcm_shortcut_valid);
+    }
+
+    #[test]
+    fn test_unclean_cycles() {
+        let analysis = analyze(UNCLEAN_EXAMPLE);
+        assert_eq!(analysis.earliest, Some(5));
+        assert!(!analysis.lcm_shortcut_valid);
+    }
+
+    #[test]
+    fn test_lcm_shortcut_without_clean_cycles() {
+        // Hits at odd steps and at 3, 9, 15, ...: not at the multiples of
+        // the first hits, yet their LCM is still the earliest common hit.
+        let analysis = Analysis::new(vec![
+            Cycle {
+                tail: 1,
+                length: 2,
+                tail_hits: vec![],
+                cycle_hits: vec![1],
+            },
+            Cycle {
+                tail: 3,
+                length: 6,
+                tail_hits: vec![],
+                cycle_hits: vec![3],
+            },
+        ]);
+
+        assert_eq!(analysis.earliest, Some(3));
+        assert!(analysis.lcm_shortcut_valid);
+    }
+
+    #[test]
+    fn test_never_meeting() {
+        // Ghost 1 hits at odd steps and ghost 2 at even ones.
+        let puzzle = "L
+
+1A = (1Z, 1Z)
+1Z = (1B, 1B)
+1B = (1Z, 1Z)
+2A = (2B, 2B)
+2B = (2Z, 2Z)
+2Z = (2B, 2B)
+";
+
+        let analysis = analyze(puzzle);
+        assert_eq!(analysis.earliest, None);
+        assert!(!analysis.lcm_shortcut_valid);
+    }
+
+    #[test]
+    fn test_hit_in_tail() {
+        // Both ghosts are on an end node at step 1 but never again together.
+        let puzzle = "L
+
+1A = (1Z, 1Z)
+1Z = (1B, 1B)
+1B = (1B, 1B)
+2A = (2Z, 2Z)
+2Z = (2Z, 2Z)
+";
+
+        let analysis = analyze(puzzle);
+        assert_eq!(analysis.earliest, Some(1));
+        assert_eq!(analysis.cycles[0].tail_hits, vec![1]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let network = Network::parse(LONG_EXAMPLE).unwrap();
+        let start = network.id("11A").unwrap();
+
+        let path = network.trace(start, |id| id.ends_with('Z'));
+        let names: Vec<_> = path.iter().map(|n| network.name(*n)).collect();
+        assert_eq!(names, vec!["11A", "11B", "11Z"]);
+
+        // Without goals it stops once it loops: 11A, then 11B and 11Z forever.
+        assert_eq!(network.trace(start, |_| false).len(), 4);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let puzzle = "LR
+
+AAA = (BBB, CCC)
+BBB = (ZZZ, ZZZ)
+CCC = (CCC, CCC)
+ZZZ = (ZZZ, ZZZ)
+";
+        let network = Network::parse(puzzle).unwrap();
+        let path = network.trace(network.id("AAA").unwrap(), |id| id == "ZZZ");
+
+        assert_eq!(
+            network.to_dot(|id| id == "AAA", |id| id == "ZZZ", &[path]),
+            r#"digraph network {
+    "AAA" [style=filled, fillcolor=palegreen];
+    "ZZZ" [style=filled, fillcolor=lightcoral];
+    "AAA" -> "BBB" [label="L @ 0", color=red, penwidth=2];
+    "AAA" -> "CCC" [label="R"];
+    "BBB" -> "ZZZ" [label="L/R @ 1", color=red, penwidth=2];
+    "CCC" -> "CCC" [label="L/R"];
+    "ZZZ" -> "ZZZ" [label="L/R"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_reachability() {
+        let puzzle = "L
+
+1A = (1Z, 1Z)
+1Z = (1B, 1B)
+1B = (1Z, 1Z)
+2A = (2B, 2Z)
+2B = (2C, 2C)
+2C = (2C, 2C)
+2Z = (2Z, 2Z)
+";
+        let network = Network::parse(puzzle).unwrap();
+        let report = network.reachability(|id| id.ends_with('A'), |id| id.ends_with('Z'));
+
+        // 2A could go to 2Z, but only ever goes left.
+        assert_eq!(
+            report.to_string(),
+            "1A: reachable [1Z], walked [1Z]
+2A: reachable [2Z], walked []
+dead ends: [2B, 2C]
+"
+        );
+
+        // Same as searching forwards from every node.
+        let ends: Vec<u32> = network.find_all(&NodePattern::parse("*Z")).collect();
+        let reaching = network.reaching(&ends);
+        for node in network.nodes() {
+            let reachable = network.reachable_from(node);
+            assert_eq!(
+                reaching[node as usize],
+                ends.iter().any(|e| reachable[*e as usize])
+            );
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((1, 2), (2, 3)), Some((5, 6)));
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1, 2), (0, 4)), None);
+    }
+}
@@ -0,0 +1,323 @@
+//! Mirage Maintenance (day 9).
+//!
+//! A history of `n` values that ends up with all-zero differences is a
+//! polynomial of the value index, and its Newton form
+//! `value(i) = sum(d[j] * C(i, j))` only needs the first value of each level
+//! of the difference table, `d[j]`. That's enough to extrapolate any number
+//! of steps in either direction and to recover the polynomial itself.
+
+use std::fmt;
+
+use crate::math::gcd;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OasisError {
+    EmptyHistory,
+    /// The differences never became all zeros, so the history is either not
+    /// a polynomial or needs at least `min_len` values to tell.
+    NotPolynomial {
+        len: usize,
+        min_len: usize,
+    },
+    /// Some value didn't fit in the integer type.
+    Overflow,
+}
+
+impl fmt::Display for OasisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyHistory => write!(f, "the history has no values"),
+            Self::NotPolynomial { len, min_len } => write!(
+                f,
+                "not polynomial within {} values, at least {} are needed",
+                len, min_len
+            ),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for OasisError {}
+
+/// An exact fraction, always in its lowest terms with a positive
+/// denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "zero denominator");
+
+        let g = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()).max(1) as i128
+            * denominator.signum();
+
+        Self {
+            numerator: numerator / g,
+            denominator: denominator / g,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let numerator = self
+            .numerator
+            .checked_mul(other.denominator)?
+            .checked_add(other.numerator.checked_mul(self.denominator)?)?;
+        let denominator = self.denominator.checked_mul(other.denominator)?;
+
+        Some(Self::new(numerator, denominator))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// Polynomial model of an OASIS history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Oasis {
+    /// First value of every level of the difference table, down to the last
+    /// level that isn't all zeros.
+    newton: Vec<i64>,
+    len: usize,
+}
+
+impl Oasis {
+    pub fn new(history: &[i64]) -> Result<Self, OasisError> {
+        if history.is_empty() {
+            return Err(OasisError::EmptyHistory);
+        }
+
+        let mut newton = vec![history[0]];
+        let mut level = history.to_vec();
+
+        loop {
+            level = level
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]).ok_or(OasisError::Overflow))
+                .collect::<Result<_, _>>()?;
+
+            // A degree `d` polynomial is only confirmed by a level `d + 1`
+            // with at least one value, all zeros.
+            if level.is_empty() {
+                return Err(OasisError::NotPolynomial {
+                    len: history.len(),
+                    min_len: history.len() + 1,
+                });
+            }
+
+            if level.iter().all(|n| *n == 0) {
+                break;
+            }
+
+            newton.push(level[0]);
+        }
+
+        Ok(Self {
+            newton,
+            len: history.len(),
+        })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.newton.len() - 1
+    }
+
+    /// Shortest history that can confirm a polynomial of this degree: one
+    /// value more than needed to pin it down, so the last difference level
+    /// can be seen to be zero.
+    pub fn min_history_len(&self) -> usize {
+        self.degree() + 2
+    }
+
+    /// Value at `index`, where the history goes from `0` to `len - 1`. Any
+    /// other index, including negative ones, is extrapolated.
+    pub fn at(&self, index: i64) -> Result<i64, OasisError> {
+        let n = index as i128;
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+
+        for (j, d) in self.newton.iter().enumerate() {
+            if j > 0 {
+                // C(n, j) = C(n, j - 1) * (n - j + 1) / j, exact for any n.
+                binomial = binomial
+                    .checked_mul(n - j as i128 + 1)
+                    .ok_or(OasisError::Overflow)?
+                    / j as i128;
+            }
+
+            let term = binomial
+                .checked_mul(*d as i128)
+                .ok_or(OasisError::Overflow)?;
+            value = value.checked_add(term).ok_or(OasisError::Overflow)?;
+        }
+
+        i64::try_from(value).map_err(|_| OasisError::Overflow)
+    }
+
+    /// Value `steps` after the last one in the history, or before the first
+    /// one if `steps` is negative.
+    pub fn extrapolate(&self, steps: i64) -> Result<i64, OasisError> {
+        let index = if steps >= 0 {
+            (self.len as i64 - 1).checked_add(steps)
+        } else {
+            Some(steps)
+        };
+
+        self.at(index.ok_or(OasisError::Overflow)?)
+    }
+
+    /// Coefficients of the polynomial from the constant term up, so that
+    /// `value(i) = c[0] + c[1] * i + c[2] * i^2 + ...`.
+    pub fn coefficients(&self) -> Result<Vec<Rational>, OasisError> {
+        let mut coefficients = vec![Rational::new(0, 1); self.newton.len()];
+        // Falling factorial i * (i - 1) * ... as coefficients of powers of i.
+        let mut falling: Vec<i128> = vec![1];
+        let mut factorial: i128 = 1;
+
+        for (j, d) in self.newton.iter().enumerate() {
+            if j > 0 {
+                let shift = j as i128 - 1;
+                let mut next = vec![0; falling.len() + 1];
+
+                for (power, c) in falling.iter().enumerate() {
+                    next[power + 1] = c.checked_add(next[power + 1]).ok_or(OasisError::Overflow)?;
+                    next[power] = c
+                        .checked_mul(shift)
+                        .and_then(|product| next[power].checked_sub(product))
+                        .ok_or(OasisError::Overflow)?;
+                }
+
+                falling = next;
+                factorial = factorial
+                    .checked_mul(j as i128)
+                    .ok_or(OasisError::Overflow)?;
+            }
+
+            for (power, c) in falling.iter().enumerate() {
+                let numerator = c.checked_mul(*d as i128).ok_or(OasisError::Overflow)?;
+                coefficients[power] = coefficients[power]
+                    .checked_add(Rational::new(numerator, factorial))
+                    .ok_or(OasisError::Overflow)?;
+            }
+        }
+
+        Ok(coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINEAR: [i64; 6] = [0, 3, 6, 9, 12, 15];
+    const QUADRATIC: [i64; 6] = [1, 3, 6, 10, 15, 21];
+    const CUBIC: [i64; 6] = [10, 13, 16, 21, 30, 45];
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(Oasis::new(&[7, 7, 7]).unwrap().degree(), 0);
+        assert_eq!(Oasis::new(&LINEAR).unwrap().degree(), 1);
+        assert_eq!(Oasis::new(&QUADRATIC).unwrap().degree(), 2);
+        assert_eq!(Oasis::new(&CUBIC).unwrap().degree(), 3);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let cubic = Oasis::new(&CUBIC).unwrap();
+        assert_eq!(cubic.extrapolate(1), Ok(68));
+        assert_eq!(cubic.extrapolate(-1), Ok(5));
+
+        let quadratic = Oasis::new(&QUADRATIC).unwrap();
+        assert_eq!(quadratic.extrapolate(3), Ok(45));
+        assert_eq!(quadratic.extrapolate(-3), Ok(1));
+
+        for (i, value) in CUBIC.iter().enumerate() {
+            assert_eq!(cubic.at(i as i64), Ok(*value));
+        }
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let show = |history: &[i64]| {
+            Oasis::new(history)
+                .unwrap()
+                .coefficients()
+                .unwrap()
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(show(&LINEAR), vec!["0", "3"]);
+        // (i + 1) * (i + 2) / 2
+        assert_eq!(show(&QUADRATIC), vec!["1", "3/2", "1/2"]);
+        assert_eq!(show(&CUBIC), vec!["10", "11/3", "-1", "1/3"]);
+    }
+
+    #[test]
+    fn test_not_polynomial() {
+        assert_eq!(
+            Oasis::new(&[1, 2, 4, 8, 16]),
+            Err(OasisError::NotPolynomial { len: 5, min_len: 6 })
+        );
+        assert_eq!(
+            Oasis::new(&[5]),
+            Err(OasisError::NotPolynomial { len: 1, min_len: 2 })
+        );
+        assert_eq!(Oasis::new(&[]), Err(OasisError::EmptyHistory));
+
+        // Just enough values to confirm a constant.
+        assert_eq!(Oasis::new(&[5, 5]).unwrap().extrapolate(1), Ok(5));
+    }
+
+    #[test]
+    fn test_min_history_len() {
+        assert_eq!(Oasis::new(&[7, 7]).unwrap().min_history_len(), 2);
+        assert_eq!(Oasis::new(&QUADRATIC).unwrap().min_history_len(), 4);
+        assert_eq!(Oasis::new(&CUBIC).unwrap().min_history_len(), 5);
+
+        // With 3 values there are no third differences to check.
+        assert!(Oasis::new(&QUADRATIC[..3]).is_err());
+        assert!(Oasis::new(&QUADRATIC[..4]).is_ok());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let oasis = Oasis::new(&[i64::MAX - 3, i64::MAX - 2, i64::MAX - 1]).unwrap();
+        assert_eq!(oasis.extrapolate(1), Ok(i64::MAX));
+        assert_eq!(oasis.extrapolate(2), Err(OasisError::Overflow));
+
+        assert_eq!(Oasis::new(&[i64::MIN, i64::MAX]), Err(OasisError::Overflow));
+
+        // C(i, 36): small values, but the coefficients of i^k don't fit.
+        let mut history = vec![0; 36];
+        history.extend([1, 37]);
+        let oasis = Oasis::new(&history).unwrap();
+        assert_eq!(oasis.extrapolate(1), Ok(703));
+        assert_eq!(oasis.coefficients(), Err(OasisError::Overflow));
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+        assert_eq!(Rational::new(0, 5).to_string(), "0");
+        assert_eq!(Rational::new(6, 4).to_string(), "3/2");
+    }
+}
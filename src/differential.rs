@@ -0,0 +1,619 @@
+//! Differential testing of solution variants.
+//!
+//! Different ways of solving the same part must give the same answer.
+//! [`Differential`] runs all of them on generated inputs, along with the
+//! answer the generator knows, if any. When they disagree, the input is
+//! shrunk to a small one they still disagree on and saved as a regression
+//! fixture in `examples/dayNN`. Saved regressions are replayed first on every
+//! run, checked against their `.answers` file once it's been written.
+
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::fixtures;
+use crate::generate::{self, Options};
+use crate::report::fingerprint;
+
+/// What a variant gave for an input: its answer, or the panic message.
+pub type Outcome = Result<String, String>;
+
+struct Variant<'a> {
+    name: String,
+    solve: Box<dyn Fn(&str) -> String + 'a>,
+}
+
+impl Variant<'_> {
+    fn run(&self, input: &str) -> Outcome {
+        catch_panic(|| (self.solve)(input))
+    }
+}
+
+/// Runs `f`, turning a panic into its message.
+pub(crate) fn catch_panic(f: impl FnOnce() -> String) -> Outcome {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())
+    })
+}
+
+/// Variants of a day part and the inputs to run them on.
+pub struct Differential<'a> {
+    day: u8,
+    part: u8,
+    variants: Vec<Variant<'a>>,
+    seeds: Range<u64>,
+    sizes: Vec<usize>,
+    /// Everything but the seed and size, which are set for every input.
+    generator: Options,
+    fixtures: Option<PathBuf>,
+}
+
+impl<'a> Differential<'a> {
+    /// Runs on seeds `0..20` at the generator's default size, saving
+    /// regressions in `examples/dayNN`.
+    pub fn new(day: u8, part: u8) -> Self {
+        Self {
+            day,
+            part,
+            variants: Vec::new(),
+            seeds: 0..20,
+            sizes: Vec::new(),
+            generator: Options::default(),
+            fixtures: Some(fixtures_dir(day)),
+        }
+    }
+
+    pub fn variant<T: ToString>(
+        mut self,
+        name: impl Into<String>,
+        solve: impl Fn(&str) -> T + 'a,
+    ) -> Self {
+        self.variants.push(Variant {
+            name: name.into(),
+            solve: Box::new(move |input| solve(input).to_string()),
+        });
+        self
+    }
+
+    pub fn seeds(mut self, seeds: Range<u64>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    /// Every seed is tried with every size.
+    pub fn sizes(mut self, sizes: &[usize]) -> Self {
+        self.sizes = sizes.to_vec();
+        self
+    }
+
+    /// Day 8 cycle lengths, see [`Options::cycles`].
+    pub fn cycles(mut self, cycles: &[u64]) -> Self {
+        self.generator.cycles = cycles.to_vec();
+        self
+    }
+
+    /// Day 8 steps before the cycles, see [`Options::tail`].
+    pub fn tail(mut self, tail: u64) -> Self {
+        self.generator.tail = tail;
+        self
+    }
+
+    /// Day 8 goals in every cycle and how far the last one is from its end,
+    /// see [`Options::goals`] and [`Options::goal_offset`].
+    pub fn goals(mut self, goals: u64, goal_offset: u64) -> Self {
+        self.generator.goals = goals;
+        self.generator.goal_offset = goal_offset;
+        self
+    }
+
+    /// Where to save regressions, or `None` to not save them.
+    pub fn fixtures(mut self, dir: Option<PathBuf>) -> Self {
+        self.fixtures = dir;
+        self
+    }
+
+    /// Runs every variant on every saved regression of the part and then on
+    /// every generated input, returning how many inputs were tried, or the
+    /// first disagreement.
+    pub fn run(&self) -> Result<usize, Box<Mismatch>> {
+        let mut tried = self.replay()?;
+
+        let sizes: Vec<Option<usize>> = if self.sizes.is_empty() {
+            vec![None]
+        } else {
+            self.sizes.iter().map(|s| Some(*s)).collect()
+        };
+
+        for seed in self.seeds.clone() {
+            for size in sizes.iter() {
+                let options = Options {
+                    seed,
+                    size: *size,
+                    ..self.generator.clone()
+                };
+                let generated = generate::generate(self.day, &options)
+                    .unwrap_or_else(|| panic!("no generator for day {}", self.day));
+                let expected = generated.answers[self.part as usize - 1].as_deref();
+
+                let outcomes = self.outcomes(&generated.input);
+                tried += 1;
+
+                if agree(&outcomes, expected) {
+                    continue;
+                }
+
+                return Err(Box::new(self.mismatch(
+                    options,
+                    generated.input,
+                    outcomes,
+                    expected,
+                )));
+            }
+        }
+
+        Ok(tried)
+    }
+
+    /// Runs every variant on the `regression-p{part}-*.txt` fixtures, which
+    /// are already as small as they get, so disagreements are reported as
+    /// they are.
+    fn replay(&self) -> Result<usize, Box<Mismatch>> {
+        let Some(dir) = &self.fixtures else {
+            return Ok(0);
+        };
+
+        let prefix = format!("regression-p{}-", self.part);
+        let mut tried = 0;
+
+        for fixture in fixtures::load_dir(dir) {
+            if !fixture.name.starts_with(&prefix) {
+                continue;
+            }
+
+            let expected = fixture.answers[self.part as usize - 1].as_deref();
+            let outcomes = self.outcomes(&fixture.input);
+            tried += 1;
+
+            if agree(&outcomes, expected) {
+                continue;
+            }
+
+            let mut answers = self.named(outcomes);
+            if let Some(expected) = expected {
+                answers.push(("answers file".to_string(), Ok(expected.to_string())));
+            }
+
+            return Err(Box::new(Mismatch {
+                day: self.day,
+                part: self.part,
+                options: None,
+                input: fixture.input,
+                answers,
+                fixture: Some(dir.join(format!("{}.txt", fixture.name))),
+            }));
+        }
+
+        Ok(tried)
+    }
+
+    /// Same as [`Differential::run`], panicking on a disagreement.
+    pub fn check(&self) {
+        if let Err(mismatch) = self.run() {
+            panic!("{}", mismatch);
+        }
+    }
+
+    fn outcomes(&self, input: &str) -> Vec<Outcome> {
+        self.variants.iter().map(|v| v.run(input)).collect()
+    }
+
+    /// `outcomes` next to the name of the variant each is from.
+    fn named(&self, outcomes: Vec<Outcome>) -> Vec<(String, Outcome)> {
+        self.variants
+            .iter()
+            .map(|v| v.name.clone())
+            .zip(outcomes)
+            .collect()
+    }
+
+    fn mismatch(
+        &self,
+        options: Options,
+        input: String,
+        outcomes: Vec<Outcome>,
+        expected: Option<&str>,
+    ) -> Mismatch {
+        // The generator can't answer for shrunk inputs, so when only it
+        // disagrees there's nothing to shrink towards.
+        let shrunk = differ(&outcomes);
+        let (input, outcomes) = if shrunk {
+            let input = shrink(&input, |candidate| differ(&self.outcomes(candidate)));
+            let outcomes = self.outcomes(&input);
+            (input, outcomes)
+        } else {
+            (input, outcomes)
+        };
+
+        let fixture = self.fixtures.as_ref().and_then(|dir| {
+            let path = dir.join(format!(
+                "regression-p{}-{:016x}.txt",
+                self.part,
+                fingerprint(&input)
+            ));
+
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(&path, &input))
+                .ok()
+                .map(|_| path)
+        });
+
+        let mut answers = self.named(outcomes);
+
+        if let Some(expected) = expected.filter(|_| !shrunk) {
+            answers.push(("generator".to_string(), Ok(expected.to_string())));
+        }
+
+        Mismatch {
+            day: self.day,
+            part: self.part,
+            options: Some(options),
+            input,
+            answers,
+            fixture,
+        }
+    }
+}
+
+/// Whether every variant answered the same, and the same as the generator
+/// if it knows the answer.
+fn agree(outcomes: &[Outcome], expected: Option<&str>) -> bool {
+    outcomes.iter().all(|outcome| match (outcome, expected) {
+        (Err(_), _) => false,
+        (Ok(answer), Some(expected)) => answer == expected,
+        (Ok(_), None) => true,
+    }) && !differ(outcomes)
+}
+
+/// Whether the variants disagree among themselves. All panics count as the
+/// same outcome, so an input every variant rejects isn't interesting.
+fn differ(outcomes: &[Outcome]) -> bool {
+    let normalized: Vec<Option<&String>> = outcomes.iter().map(|o| o.as_ref().ok()).collect();
+    normalized.windows(2).any(|w| w[0] != w[1])
+}
+
+/// Removes as much of `input` as possible while it stays `interesting`:
+/// first chunks of lines, halving the chunk size when nothing more can be
+/// removed, then single words of each line.
+pub fn shrink(input: &str, interesting: impl Fn(&str) -> bool) -> String {
+    let join = |lines: &[String]| {
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    };
+
+    let mut lines: Vec<String> = input.lines().map(|l| l.to_string()).collect();
+    let mut chunk = lines.len().div_ceil(2);
+
+    while chunk > 0 {
+        let mut start = 0;
+        let mut removed = false;
+
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<String> = lines[..start]
+                .iter()
+                .chain(&lines[end..])
+                .cloned()
+                .collect();
+
+            if interesting(&join(&candidate)) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if !removed {
+            chunk /= 2;
+        }
+    }
+
+    for i in 0..lines.len() {
+        let mut word = 0;
+
+        loop {
+            let words: Vec<&str> = lines[i].split(' ').collect();
+            // An empty line has a single empty word, and removing it changes
+            // nothing.
+            if word >= words.len() || lines[i].is_empty() {
+                break;
+            }
+
+            let shorter = words
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != word)
+                .map(|(_, w)| *w)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let mut candidate = lines.clone();
+            candidate[i] = shorter;
+
+            if interesting(&join(&candidate)) {
+                lines = candidate;
+            } else {
+                word += 1;
+            }
+        }
+    }
+
+    join(&lines)
+}
+
+/// `examples/dayNN` in the crate.
+pub fn fixtures_dir(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(format!("day{:02}", day))
+}
+
+/// Variants that didn't agree on an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    /// What the input was generated with, before shrinking, or `None` for a
+    /// saved regression.
+    pub options: Option<Options>,
+    pub input: String,
+    pub answers: Vec<(String, Outcome)>,
+    /// Where the input was saved, if it was.
+    pub fixture: Option<PathBuf>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.options {
+            Some(options) => writeln!(
+                f,
+                "day {} part {} variants disagree on the input of seed {}, size {}:",
+                self.day,
+                self.part,
+                options.seed,
+                options
+                    .size
+                    .map_or("default".to_string(), |s| s.to_string())
+            )?,
+            None => writeln!(
+                f,
+                "day {} part {} variants disagree on a saved regression:",
+                self.day, self.part
+            )?,
+        }
+
+        for (name, outcome) in self.answers.iter() {
+            match outcome {
+                Ok(answer) => writeln!(f, "  {}: {}", name, answer)?,
+                Err(message) => writeln!(f, "  {}: panicked: {}", name, message)?,
+            }
+        }
+
+        if let Some(path) = &self.fixture {
+            writeln!(f, "saved as {}", path.display())?;
+        }
+
+        write!(f, "{}", self.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d9::Oasis;
+
+    fn count_lines(input: &str) -> usize {
+        input.lines().count()
+    }
+
+    /// Wrong when a history has a negative number.
+    fn count_lines_badly(input: &str) -> usize {
+        count_lines(input) + input.lines().filter(|l| l.contains('-')).count().min(1)
+    }
+
+    /// Day 9 part 1, by building the whole difference table.
+    fn extrapolate(input: &str) -> i64 {
+        input
+            .lines()
+            .map(|line| {
+                let mut values: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|n| n.parse().unwrap())
+                    .collect();
+                let mut next = 0;
+
+                while values.iter().any(|v| *v != 0) {
+                    next += values[values.len() - 1];
+                    values = values.windows(2).map(|w| w[1] - w[0]).collect();
+                }
+
+                next
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_agreeing_variants() {
+        let tried = Differential::new(9, 1)
+            .variant("differences", extrapolate)
+            .variant("oasis", |input: &str| -> i64 {
+                input
+                    .lines()
+                    .map(|line| {
+                        let history: Vec<i64> = line
+                            .split_whitespace()
+                            .map(|n| n.parse().unwrap())
+                            .collect();
+                        Oasis::new(&history).unwrap().extrapolate(1).unwrap()
+                    })
+                    .sum()
+            })
+            .seeds(0..3)
+            .sizes(&[1, 5])
+            .fixtures(None)
+            .run();
+
+        assert_eq!(tried, Ok(6));
+    }
+
+    #[test]
+    fn test_shrinks_and_saves_disagreements() {
+        let dir =
+            std::env::temp_dir().join(format!("aoc-2023-differential-{}", std::process::id()));
+
+        let mismatch = Differential::new(9, 1)
+            .variant("lines", count_lines)
+            .variant("bad lines", count_lines_badly)
+            .sizes(&[30])
+            .fixtures(Some(dir.clone()))
+            .run()
+            .unwrap_err();
+
+        // A single line with a single word, which must be negative.
+        assert_eq!(mismatch.input.lines().count(), 1);
+        assert!(!mismatch.input.trim().contains(' '));
+        assert!(mismatch.input.starts_with('-'));
+        assert_eq!(
+            mismatch.answers,
+            [
+                ("lines".to_string(), Ok("1".to_string())),
+                ("bad lines".to_string(), Ok("2".to_string()))
+            ]
+        );
+
+        let fixture = mismatch.fixture.unwrap();
+        assert!(fixture.starts_with(&dir));
+        assert_eq!(fs::read_to_string(&fixture).unwrap(), mismatch.input);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_disagreeing_with_the_generator() {
+        let mismatch = Differential::new(9, 1)
+            .variant("lines", count_lines)
+            .seeds(4..5)
+            .sizes(&[3])
+            .fixtures(None)
+            .run()
+            .unwrap_err();
+
+        assert_eq!(mismatch.options.unwrap().seed, 4);
+        assert_eq!(mismatch.input.lines().count(), 3);
+        assert_eq!(
+            mismatch.answers[0],
+            ("lines".to_string(), Ok("3".to_string()))
+        );
+        assert_eq!(mismatch.answers[1].0, "generator");
+    }
+
+    #[test]
+    fn test_panics_are_outcomes() {
+        let mismatch = Differential::new(9, 2)
+            .variant("lines", count_lines)
+            .variant("no negatives", |input: &str| {
+                assert!(!input.contains('-'), "negative number");
+                count_lines(input)
+            })
+            .sizes(&[30])
+            .fixtures(None)
+            .run()
+            .unwrap_err();
+
+        assert_eq!(mismatch.input.lines().count(), 1);
+        assert!(mismatch.input.starts_with('-'));
+        assert_eq!(
+            mismatch.answers,
+            [
+                ("lines".to_string(), Ok("1".to_string())),
+                (
+                    "no negatives".to_string(),
+                    Err("negative number".to_string())
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replays_regressions() {
+        let dir = std::env::temp_dir().join(format!("aoc-2023-regressions-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("regression-p1-1.txt"), "-1\n").unwrap();
+        // Only part 2 and other fixtures are left alone.
+        fs::write(dir.join("regression-p2-1.txt"), "not a history\n").unwrap();
+        fs::write(dir.join("example.txt"), "not a history\n").unwrap();
+
+        let differential = Differential::new(9, 1)
+            .variant("lines", count_lines)
+            .variant("bad lines", count_lines_badly)
+            .seeds(0..0)
+            .fixtures(Some(dir.clone()));
+
+        let mismatch = differential.run().unwrap_err();
+        assert_eq!(mismatch.options, None);
+        assert_eq!(mismatch.input, "-1\n");
+        assert_eq!(mismatch.fixture, Some(dir.join("regression-p1-1.txt")));
+        assert_eq!(
+            mismatch.answers,
+            [
+                ("lines".to_string(), Ok("1".to_string())),
+                ("bad lines".to_string(), Ok("2".to_string()))
+            ]
+        );
+
+        // Once the answer is known, agreeing isn't enough.
+        fs::write(dir.join("regression-p1-1.answers"), "part 1: 2\n").unwrap();
+        let mismatch = Differential::new(9, 1)
+            .variant("lines", count_lines)
+            .seeds(0..0)
+            .fixtures(Some(dir.clone()))
+            .run()
+            .unwrap_err();
+        assert_eq!(
+            mismatch.answers,
+            [
+                ("lines".to_string(), Ok("1".to_string())),
+                ("answers file".to_string(), Ok("2".to_string()))
+            ]
+        );
+
+        let tried = Differential::new(9, 1)
+            .variant("bad lines", count_lines_badly)
+            .seeds(0..0)
+            .fixtures(Some(dir.clone()))
+            .run();
+        assert_eq!(tried, Ok(1));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_shrink() {
+        let input = "a\nb\nc x y\nd\n";
+        let shrunk = shrink(input, |candidate| {
+            candidate.contains('x') && candidate.contains('b')
+        });
+
+        assert_eq!(shrunk, "b\nx\n");
+
+        // Empty lines that can't go, like the ones between sections.
+        let shrunk = shrink("a b\n\nc\n", |candidate| candidate.contains("\n\nc"));
+        assert_eq!(shrunk, "\n\nc\n");
+    }
+}
@@ -0,0 +1,190 @@
+//! Example inputs kept as files instead of string literals in the tests.
+//!
+//! Fixtures live in `examples/dayNN`. `name.txt` is the input, and
+//! `name.answers` has the expected answers, one `part N: answer` line per
+//! part, the same format `generate` prints. A part without an answer isn't
+//! checked, so an example of part 2 that isn't valid for part 1, or a
+//! regression saved by [`crate::differential`] before anyone knew the right
+//! answer, only needs the answers that apply.
+//!
+//! Adding a case only takes new files: every solution checks all the
+//! fixtures of its day with [`fixture_tests!`](crate::fixture_tests).
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::differential::{self, fixtures_dir};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub name: String,
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+/// Parses the `part N: answer` lines of an answers file.
+fn parse_answers(text: &str, path: &Path) -> [Option<String>; 2] {
+    let mut answers = [None, None];
+
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (part, answer) = line
+            .strip_prefix("part ")
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(part, answer)| Some((part.parse::<usize>().ok()?, answer.trim())))
+            .filter(|(part, _)| (1..=2).contains(part))
+            .unwrap_or_else(|| {
+                panic!(
+                    "{}:{}: expected \"part <1|2>: <answer>\"",
+                    path.display(),
+                    number + 1
+                )
+            });
+
+        answers[part - 1] = Some(answer.to_string());
+    }
+
+    answers
+}
+
+/// Every fixture of `day`, sorted by name.
+pub fn load(day: u8) -> Vec<Fixture> {
+    load_dir(&fixtures_dir(day))
+}
+
+/// Every fixture in `dir`, sorted by name. A missing directory has none.
+pub fn load_dir(dir: &Path) -> Vec<Fixture> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut fixtures: Vec<Fixture> = entries
+        .map(|entry| entry.expect("can not read fixtures").path())
+        .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+        .map(|path| {
+            let answers_path = path.with_extension("answers");
+            let answers = match fs::read_to_string(&answers_path) {
+                Ok(text) => parse_answers(&text, &answers_path),
+                Err(_) => [None, None],
+            };
+
+            Fixture {
+                name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+                input: fs::read_to_string(&path)
+                    .unwrap_or_else(|e| panic!("can not read {}: {}", path.display(), e)),
+                answers,
+            }
+        })
+        .collect();
+
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    fixtures
+}
+
+/// The input of fixture `name` of `day`, for tests that need an example
+/// for something other than its answer.
+pub fn read(day: u8, name: &str) -> String {
+    let path = fixtures_dir(day).join(format!("{}.txt", name));
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("can not read {}: {}", path.display(), e))
+}
+
+/// Runs `solve` on every fixture of `day` with an answer for `part`,
+/// panicking with all the wrong ones. Having no such fixture at all is an
+/// error too, so a wrong day doesn't pass silently.
+pub fn check<T: fmt::Display>(day: u8, part: u8, solve: impl Fn(&str) -> T) {
+    let fixtures: Vec<Fixture> = load(day)
+        .into_iter()
+        .filter(|f| f.answers[part as usize - 1].is_some())
+        .collect();
+
+    assert!(
+        !fixtures.is_empty(),
+        "no fixtures with a part {} answer in {}",
+        part,
+        fixtures_dir(day).display()
+    );
+
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            let expected = fixture.answers[part as usize - 1].as_deref().unwrap();
+
+            match differential::catch_panic(|| solve(&fixture.input).to_string()) {
+                Ok(answer) if answer == expected => None,
+                Ok(answer) => Some(format!(
+                    "{}: expected {}, got {}",
+                    fixture.name, expected, answer
+                )),
+                Err(message) => Some(format!(
+                    "{}: expected {}, panicked: {}",
+                    fixture.name, expected, message
+                )),
+            }
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "day {} part {} fixtures failed:\n{}",
+        day,
+        part,
+        failures.join("\n")
+    );
+}
+
+/// Adds a `test_fixtures` test checking `solve` against every fixture of
+/// `day` with an answer for `part`, see [`check`].
+#[macro_export]
+macro_rules! fixture_tests {
+    ($day:expr, $part:expr, $solve:expr) => {
+        #[test]
+        fn test_fixtures() {
+            $crate::fixtures::check($day, $part, $solve);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let path = Path::new("example.answers");
+
+        assert_eq!(
+            parse_answers("part 1: 142\n\npart 2:  -3 \n", path),
+            [Some("142".to_string()), Some("-3".to_string())]
+        );
+        assert_eq!(
+            parse_answers("part 2: 6\n", path),
+            [None, Some("6".to_string())]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "example.answers:2: expected \"part <1|2>: <answer>\"")]
+    fn test_parse_answers_errors() {
+        parse_answers("part 1: 1\npart 3: 2\n", Path::new("example.answers"));
+    }
+
+    #[test]
+    fn test_load() {
+        let fixtures = load(8);
+        let names: Vec<&str> = fixtures.iter().map(|f| f.name.as_str()).collect();
+
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+        assert!(names.contains(&"example-ghosts"));
+        assert!(load(99).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "example: expected 142, got 0")]
+    fn test_check_reports_failures() {
+        check(1, 1, |_| 0);
+    }
+}
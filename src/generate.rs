@@ -0,0 +1,1148 @@
+//! Synthetic puzzle inputs.
+//!
+//! Real inputs can't be published, so these generators make valid inputs of
+//! any size out of a seed, always the same input for the same seed. When the
+//! generator knows the answers from how it built the input, or can get them
+//! in some way that doesn't share code with the solutions, they are returned
+//! along with it.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::math::lcm;
+
+/// xorshift64, deterministic and good enough to make up puzzles.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero is the only state xorshift never leaves.
+        let mut rng = Self(seed ^ 0x9e37_79b9_7f4a_7c15);
+        rng.next_u64();
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        self.next_u64() % n
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low + 1) as u64) as i64
+    }
+
+    /// `true` once every `n` times on average.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub seed: u64,
+    /// What "size" means depends on the day, see [`generate`]. Each day has
+    /// its own default.
+    pub size: Option<usize>,
+    /// Day 8 cycle lengths, in passes through the directions.
+    pub cycles: Vec<u64>,
+    /// Day 8 steps every ghost takes before getting into its cycle.
+    pub tail: u64,
+    /// Day 8 steps between the last goal of a cycle and its end, 0 for a
+    /// goal right before the ghost is back where the cycle started.
+    pub goal_offset: u64,
+    /// Day 8 goals in every cycle, spread evenly over it.
+    pub goals: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            size: None,
+            cycles: Vec::new(),
+            tail: 0,
+            goal_offset: 0,
+            goals: 1,
+        }
+    }
+}
+
+impl Options {
+    /// `size` with the default day 8 ghosts.
+    pub fn sized(seed: u64, size: usize) -> Self {
+        Self {
+            seed,
+            size: Some(size),
+            ..Self::default()
+        }
+    }
+
+    /// Reads `--seed=<n>`, `--size=<n>`, `--cycles=<n>,...`, `--tail=<n>`,
+    /// `--goal-offset=<n>` and `--goals=<n>` from the arguments.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Self {
+        let mut options = Self::default();
+
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--seed=") {
+                options.seed = value.parse().expect("seed is not a number");
+            } else if let Some(value) = arg.strip_prefix("--size=") {
+                options.size = Some(value.parse().expect("size is not a number"));
+            } else if let Some(value) = arg.strip_prefix("--cycles=") {
+                options.cycles = value
+                    .split(',')
+                    .map(|n| n.parse().expect("cycle length is not a number"))
+                    .collect();
+            } else if let Some(value) = arg.strip_prefix("--tail=") {
+                options.tail = value.parse().expect("tail is not a number");
+            } else if let Some(value) = arg.strip_prefix("--goal-offset=") {
+                options.goal_offset = value.parse().expect("goal offset is not a number");
+            } else if let Some(value) = arg.strip_prefix("--goals=") {
+                options.goals = value.parse().expect("goals is not a number");
+            }
+        }
+
+        options
+    }
+}
+
+/// A generated input and the answers to both parts, when known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+impl Generated {
+    fn new(input: String, part1: impl ToString, part2: Option<impl ToString>) -> Self {
+        Self {
+            input,
+            answers: [Some(part1.to_string()), part2.map(|a| a.to_string())],
+        }
+    }
+}
+
+/// The days [`generate`] knows about.
+pub const DAYS: std::ops::RangeInclusive<u8> = 1..=10;
+
+/// Generates an input for `day`, or `None` if there's no generator for it.
+///
+/// The size is the number of lines for days 1, 2, 4, 7 and 9, the width and
+/// height for day 3, the number of seeds for day 5, the number of races for
+/// day 6, the length of the directions for day 8 and the length of the loop
+/// for day 10.
+pub fn generate(day: u8, options: &Options) -> Option<Generated> {
+    let mut rng = Rng::new(options.seed);
+    let size = |default: usize| options.size.unwrap_or(default).max(1);
+
+    Some(match day {
+        1 => day1(&mut rng, size(1000)),
+        2 => day2(&mut rng, size(100)),
+        3 => day3(&mut rng, size(140)),
+        4 => day4(&mut rng, size(200)),
+        5 => day5(&mut rng, size(20)),
+        6 => day6(&mut rng, size(4)),
+        7 => day7(&mut rng, size(1000)),
+        8 => {
+            let cycles = if options.cycles.is_empty() {
+                vec![2, 3, 5]
+            } else {
+                options.cycles.clone()
+            };
+            day8(&mut rng, size(50), &cycles, options)
+        }
+        9 => day9(&mut rng, size(200)),
+        10 => day10(&mut rng, size(1000)),
+        _ => return None,
+    })
+}
+
+/// Checks `solve` against the generated answer for `part` of `day`, for a
+/// few seeds and every one of `sizes`, panicking at the first wrong one.
+pub fn check_answers<T: ToString>(day: u8, part: u8, sizes: &[usize], solve: impl Fn(&str) -> T) {
+    for seed in 0..5 {
+        for size in sizes {
+            let generated = generate(day, &Options::sized(seed, *size))
+                .unwrap_or_else(|| panic!("no generator for day {}", day));
+            let expected = generated.answers[part as usize - 1]
+                .as_deref()
+                .unwrap_or_else(|| {
+                    panic!("no part {} answer for seed {} size {}", part, seed, size)
+                });
+
+            assert_eq!(
+                solve(&generated.input).to_string(),
+                expected,
+                "day {} part {}, seed {} size {}",
+                day,
+                part,
+                seed,
+                size
+            );
+        }
+    }
+}
+
+/// Lines of filler letters that can't be part of any digit word, digits,
+/// digit words and pairs of overlapping words.
+fn day1(rng: &mut Rng, lines: usize) -> Generated {
+    const FILLER: &[u8] = b"abcdjklmpqyz";
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    const OVERLAPS: [(&str, u64, u64); 6] = [
+        ("twone", 2, 1),
+        ("eightwo", 8, 2),
+        ("oneight", 1, 8),
+        ("threeight", 3, 8),
+        ("sevenine", 7, 9),
+        ("eighthree", 8, 3),
+    ];
+
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+
+    for _ in 0..lines {
+        let tokens = rng.between(1, 5);
+        let plain = rng.below(tokens as u64) as i64;
+        let mut digits = Vec::new();
+        let mut all = Vec::new();
+
+        for i in 0..tokens {
+            for _ in 0..rng.below(4) {
+                input.push(*rng.pick(FILLER) as char);
+            }
+
+            // At least one plain digit, so part 1 has something to find.
+            if i == plain || rng.one_in(3) {
+                let digit = rng.between(1, 9) as u64;
+                input.push_str(&digit.to_string());
+                digits.push(digit);
+                all.push(digit);
+            } else if rng.one_in(4) {
+                let (word, first, second) = *rng.pick(&OVERLAPS);
+                input.push_str(word);
+                all.extend([first, second]);
+            } else {
+                let digit = rng.between(1, 9) as u64;
+                input.push_str(WORDS[digit as usize - 1]);
+                all.push(digit);
+            }
+
+            // Keeps tokens apart, so the only overlaps are the known ones.
+            input.push(*rng.pick(FILLER) as char);
+        }
+
+        input.push('\n');
+        part1 += digits[0] * 10 + digits[digits.len() - 1];
+        part2 += all[0] * 10 + all[all.len() - 1];
+    }
+
+    Generated::new(input, part1, Some(part2))
+}
+
+fn day2(rng: &mut Rng, games: usize) -> Generated {
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+    const LIMITS: [u64; 3] = [12, 13, 14];
+
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+
+    for id in 1..=games {
+        let mut most = [0; 3];
+        let subsets: Vec<String> = (0..rng.between(1, 6))
+            .map(|_| {
+                let mut colors = [0, 1, 2];
+                rng.shuffle(&mut colors);
+                let shown = rng.between(1, 3) as usize;
+
+                colors[..shown]
+                    .iter()
+                    .map(|&c| {
+                        let count = rng.between(1, 20) as u64;
+                        most[c] = most[c].max(count);
+                        format!("{} {}", count, COLORS[c])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+
+        writeln!(input, "Game {}: {}", id, subsets.join("; ")).unwrap();
+
+        if most
+            .iter()
+            .zip(LIMITS)
+            .all(|(count, limit)| *count <= limit)
+        {
+            part1 += id as u64;
+        }
+        part2 += most.iter().product::<u64>();
+    }
+
+    Generated::new(input, part1, Some(part2))
+}
+
+/// A square schematic with numbers of up to 3 digits, never touching each
+/// other on the same row, and symbols scattered around.
+fn day3(rng: &mut Rng, side: usize) -> Generated {
+    const SYMBOLS: &[u8] = b"*#+$/@=%-&";
+
+    let mut grid = vec![vec![b'.'; side]; side];
+    // (row, first column, last column, value)
+    let mut numbers = Vec::new();
+
+    for (y, row) in grid.iter_mut().enumerate() {
+        let mut x = rng.below(4) as usize;
+
+        loop {
+            let len = rng.between(1, 3) as usize;
+            if x + len > side {
+                break;
+            }
+
+            let value = rng.between(10_i64.pow(len as u32 - 1), 10_i64.pow(len as u32) - 1);
+            row[x..x + len].copy_from_slice(value.to_string().as_bytes());
+            numbers.push((y, x, x + len - 1, value));
+            x += len + 1 + rng.below(6) as usize;
+        }
+    }
+
+    for row in grid.iter_mut() {
+        for cell in row.iter_mut().filter(|c| **c == b'.') {
+            if rng.one_in(10) {
+                *cell = *rng.pick(SYMBOLS);
+            }
+        }
+    }
+
+    let is_adjacent = |&(y, first, last, _): &(usize, usize, usize, i64), sy: usize, sx: usize| {
+        sy.abs_diff(y) <= 1 && sx + 1 >= first && sx <= last + 1
+    };
+
+    let mut part1 = 0;
+    let mut part2 = 0;
+
+    for number in numbers.iter() {
+        let (y, first, last, value) = *number;
+        let touches_symbol = (y.saturating_sub(1)..=(y + 1).min(side - 1)).any(|sy| {
+            (first.saturating_sub(1)..=(last + 1).min(side - 1))
+                .any(|sx| !grid[sy][sx].is_ascii_digit() && grid[sy][sx] != b'.')
+        });
+
+        if touches_symbol {
+            part1 += value;
+        }
+    }
+
+    for (sy, row) in grid.iter().enumerate() {
+        for (sx, _) in row.iter().enumerate().filter(|(_, c)| **c == b'*') {
+            let adjacent: Vec<i64> = numbers
+                .iter()
+                .filter(|n| is_adjacent(n, sy, sx))
+                .map(|n| n.3)
+                .collect();
+
+            if let [a, b] = adjacent[..] {
+                part2 += a * b;
+            }
+        }
+    }
+
+    let mut input = String::new();
+    for row in grid {
+        input.push_str(std::str::from_utf8(&row).unwrap());
+        input.push('\n');
+    }
+
+    Generated::new(input, part1, Some(part2))
+}
+
+/// Cards with a chosen number of matches, never winning copies of cards past
+/// the end of the table.
+///
+/// Copies grow exponentially, so cards that already have more than 2^40
+/// copies win nothing. A card gets copies from at most 10 cards before it,
+/// which keeps the total well within a `u64`.
+fn day4(rng: &mut Rng, cards: usize) -> Generated {
+    let width = cards.to_string().len().max(3);
+    let mut input = String::new();
+    let mut matches = Vec::new();
+    let mut instances = vec![1_u64; cards];
+
+    for id in 1..=cards {
+        let mut numbers: Vec<u64> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+
+        let mut count = rng.below(11).min((cards - id) as u64) as usize;
+        if instances[id - 1] > 1 << 40 {
+            count = 0;
+        }
+        for j in id..id + count {
+            instances[j] += instances[id - 1];
+        }
+
+        let winning = &numbers[..10];
+        let mut own: Vec<u64> = winning[..count]
+            .iter()
+            .chain(&numbers[10..35 - count])
+            .copied()
+            .collect();
+        rng.shuffle(&mut own);
+
+        let list = |numbers: &[u64]| {
+            numbers
+                .iter()
+                .map(|n| format!("{:>2}", n))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        writeln!(
+            input,
+            "Card {:>width$}: {} | {}",
+            id,
+            list(winning),
+            list(&own),
+            width = width
+        )
+        .unwrap();
+        matches.push(count);
+    }
+
+    let part1: u64 = matches
+        .iter()
+        .filter(|m| **m > 0)
+        .map(|m| 1 << (m - 1))
+        .sum();
+
+    Generated::new(input, part1, Some(instances.iter().sum::<u64>()))
+}
+
+/// An almanac with small seed ranges, so brute force solutions stay usable,
+/// and maps made of disjoint source ranges.
+fn day5(rng: &mut Rng, seeds: usize) -> Generated {
+    const NAMES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    let seeds = seeds + seeds % 2;
+    let space = seeds as i64 * 1000;
+    let seed_numbers: Vec<i64> = (0..seeds)
+        .map(|i| {
+            if i % 2 == 0 {
+                rng.between(0, space)
+            } else {
+                rng.between(1, 100)
+            }
+        })
+        .collect();
+
+    let mut input = format!(
+        "seeds: {}\n",
+        seed_numbers
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+
+    // (source start, source end, offset)
+    let mut maps: Vec<Vec<(i64, i64, i64)>> = Vec::new();
+
+    for names in NAMES.windows(2) {
+        writeln!(input, "\n{}-to-{} map:", names[0], names[1]).unwrap();
+
+        let mut ranges = Vec::new();
+        let mut start = rng.between(0, space / 20);
+        while start < space {
+            let len = rng.between(1, space / 10);
+            let target = rng.between(0, space);
+            writeln!(input, "{} {} {}", target, start, len).unwrap();
+            ranges.push((start, start + len, target - start));
+            start += len + rng.between(0, space / 20);
+        }
+
+        maps.push(ranges);
+    }
+
+    let map = |value: i64, ranges: &[(i64, i64, i64)]| {
+        ranges
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&value))
+            .map_or(value, |(_, _, offset)| value + offset)
+    };
+
+    let part1 = seed_numbers
+        .iter()
+        .map(|seed| maps.iter().fold(*seed, |value, ranges| map(value, ranges)))
+        .min()
+        .unwrap();
+
+    // Part 2 moves whole intervals through the maps, splitting them at the
+    // edges of the source ranges.
+    let mut intervals: Vec<(i64, i64)> = seed_numbers
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[0] + pair[1]))
+        .collect();
+
+    for ranges in maps.iter() {
+        let mut mapped = Vec::new();
+
+        while let Some((start, end)) = intervals.pop() {
+            let hit = ranges.iter().find(|(s, e, _)| start < *e && *s < end);
+
+            match hit {
+                None => mapped.push((start, end)),
+                Some(&(s, e, offset)) => {
+                    if start < s {
+                        intervals.push((start, s));
+                    }
+                    if e < end {
+                        intervals.push((e, end));
+                    }
+                    mapped.push((start.max(s) + offset, end.min(e) + offset));
+                }
+            }
+        }
+
+        intervals = mapped;
+    }
+
+    let part2 = intervals.iter().map(|(start, _)| *start).min().unwrap();
+
+    Generated::new(input, part1, Some(part2))
+}
+
+/// Races that can always be won. Answers are left out when they, or the
+/// kerned numbers of part 2, don't fit in an `i64`.
+fn day6(rng: &mut Rng, races: usize) -> Generated {
+    let races: Vec<(u128, u128)> = (0..races)
+        .map(|_| {
+            let time = rng.between(7, 99) as u128;
+            let best = (time / 2) * (time - time / 2);
+            (time, rng.between(1, best as i64 - 1) as u128)
+        })
+        .collect();
+
+    // The first hold that beats the record, by bisection on the rising half.
+    let ways = |time: u128, record: u128| {
+        let (mut low, mut high) = (0, time / 2 + 1);
+        while low < high {
+            let mid = (low + high) / 2;
+            if mid * (time - mid) > record {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        if low > time / 2 {
+            0
+        } else {
+            time - 2 * low + 1
+        }
+    };
+
+    let part1 = races
+        .iter()
+        .try_fold(1_u128, |acc, (t, r)| acc.checked_mul(ways(*t, *r)))
+        .filter(|product| *product <= i64::MAX as u128);
+
+    let kerned = |numbers: Vec<u128>| {
+        numbers
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<String>()
+            .parse::<i64>()
+            .ok()
+    };
+    let time = kerned(races.iter().map(|(t, _)| *t).collect());
+    let record = kerned(races.iter().map(|(_, r)| *r).collect());
+    let part2 = time.zip(record).map(|(t, r)| ways(t as u128, r as u128));
+
+    let line = |name: &str, numbers: Vec<u128>| {
+        let numbers: String = numbers.iter().map(|n| format!("{:>6}", n)).collect();
+        format!("{:<9}{}\n", format!("{}:", name), numbers)
+    };
+
+    let input = line("Time", races.iter().map(|(t, _)| *t).collect())
+        + &line("Distance", races.iter().map(|(_, r)| *r).collect());
+
+    Generated {
+        input,
+        answers: [part1.map(|a| a.to_string()), part2.map(|a| a.to_string())],
+    }
+}
+
+/// Distinct hands, each drawn from a few labels so every kind shows up.
+fn day7(rng: &mut Rng, hands: usize) -> Generated {
+    const LABELS: &[u8] = b"23456789TJQKA";
+
+    // Asking for more hands than can be dealt gives them all.
+    let hands = hands.min(LABELS.len().pow(5));
+    let mut seen = HashSet::new();
+    let mut plays = Vec::new();
+
+    while plays.len() < hands {
+        let mut labels = LABELS.to_vec();
+        rng.shuffle(&mut labels);
+        let used = rng.between(1, 5) as usize;
+        let hand: String = (0..5).map(|_| *rng.pick(&labels[..used]) as char).collect();
+
+        if seen.insert(hand.clone()) {
+            plays.push((hand, rng.between(1, 1000) as u64));
+        }
+    }
+
+    // Kind first, then the strength of each card in order.
+    let winnings = |order: &str, jokers: bool| {
+        let key = |hand: &str| {
+            let mut counts: HashMap<char, u64> = HashMap::new();
+            let mut wild = 0;
+            for c in hand.chars() {
+                if jokers && c == 'J' {
+                    wild += 1;
+                } else {
+                    *counts.entry(c).or_insert(0) += 1;
+                }
+            }
+
+            let mut counts: Vec<u64> = counts.into_values().collect();
+            counts.sort_by(|a, b| b.cmp(a));
+            if counts.is_empty() {
+                counts.push(0);
+            }
+            counts[0] += wild;
+
+            let strengths: Vec<usize> = hand.chars().map(|c| order.find(c).unwrap()).collect();
+            (counts, strengths)
+        };
+
+        let mut ranked: Vec<_> = plays.iter().map(|(hand, bid)| (key(hand), *bid)).collect();
+        ranked.sort();
+        ranked
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (i as u64 + 1) * bid)
+            .sum::<u64>()
+    };
+
+    let mut input = String::new();
+    for (hand, bid) in plays.iter() {
+        writeln!(input, "{} {}", hand, bid).unwrap();
+    }
+
+    Generated::new(
+        input,
+        winnings("23456789TJQKA", false),
+        Some(winnings("J23456789TQKA", true)),
+    )
+}
+
+/// A network where every ghost starting at a `..A` node walks `tail` steps
+/// and then into a loop of `cycles[i]` times the length of the directions,
+/// with `goals` evenly spaced `..Z` nodes, the last one `goal_offset` steps
+/// before the end. `AAA` is the first ghost and `ZZZ` its first goal.
+///
+/// Every node is only ever visited at the same position in the directions,
+/// so the direction not taken can point anywhere.
+fn day8(rng: &mut Rng, directions: usize, cycles: &[u64], options: &Options) -> Generated {
+    let tail = options.tail as usize;
+
+    let directions: Vec<char> = (0..directions)
+        .map(|_| if rng.one_in(2) { 'L' } else { 'R' })
+        .collect();
+
+    let alphabet: Vec<char> = ('A'..='Z').chain('0'..='9').collect();
+    let mut names: Vec<String> = alphabet
+        .iter()
+        .flat_map(|a| alphabet.iter().map(move |b| (a, b)))
+        .flat_map(|(a, b)| {
+            alphabet[1..25]
+                .iter()
+                .chain(&alphabet[26..])
+                .map(move |c| format!("{}{}{}", a, b, c))
+        })
+        .collect();
+    rng.shuffle(&mut names);
+
+    let needed: u64 = cycles
+        .iter()
+        .map(|c| options.tail + c * directions.len() as u64)
+        .sum();
+    assert!(
+        (needed as usize) < names.len(),
+        "too many nodes for 3 character names"
+    );
+
+    // Starts and goals of the other ghosts, which must not be AAA or ZZZ.
+    let mut prefixes: Vec<String> = alphabet
+        .iter()
+        .flat_map(|a| alphabet.iter().map(move |b| format!("{}{}", a, b)))
+        .filter(|p| p != "AA" && p != "ZZ")
+        .collect();
+    rng.shuffle(&mut prefixes);
+
+    // name -> (left, right)
+    let mut nodes: Vec<(String, [String; 2])> = Vec::new();
+    // Cycle length and steps into the cycle of every goal, for every ghost.
+    let mut ghosts: Vec<(u64, Vec<u64>)> = Vec::new();
+
+    for (ghost, multiple) in cycles.iter().enumerate() {
+        assert!(*multiple > 0, "cycle lengths must be positive");
+        let length = *multiple as usize * directions.len();
+        assert!(
+            (1..=length as u64).contains(&options.goals),
+            "a cycle needs between 1 and {} goals",
+            length
+        );
+        assert!(
+            options.goal_offset < length as u64,
+            "the goal offset must be shorter than the cycle"
+        );
+
+        // Steps into the cycle, from 1 right after the tail to length at
+        // its end.
+        let spacing = length / options.goals as usize;
+        let last = length - options.goal_offset as usize;
+        let mut positions: Vec<u64> = (0..options.goals as usize)
+            .map(|k| ((last + length - 1 - k * spacing) % length + 1) as u64)
+            .collect();
+        positions.sort();
+
+        let prefix = if ghost == 0 {
+            "AA".to_string()
+        } else {
+            prefixes
+                .pop()
+                .expect("too many ghosts for 3 character names")
+        };
+        let start = format!("{}A", prefix);
+        let mut goals = vec![if ghost == 0 {
+            "ZZZ".to_string()
+        } else {
+            format!("{}Z", prefix)
+        }];
+        while goals.len() < positions.len() {
+            let prefix = prefixes
+                .pop()
+                .expect("too many goals for 3 character names");
+            goals.push(format!("{}Z", prefix));
+        }
+        let mut goals = goals.into_iter();
+
+        // path[t] is where the ghost is after t steps. The cycle goes from
+        // path[tail + 1] to path[tail + length], and from there back to
+        // path[tail + 1].
+        let mut path = vec![start];
+        path.extend((1..=tail + length).map(|t| {
+            if t > tail && positions.contains(&((t - tail) as u64)) {
+                goals.next().unwrap()
+            } else {
+                names.pop().unwrap()
+            }
+        }));
+
+        for t in 0..=tail + length {
+            let next = if t == tail + length {
+                path[tail + 1].clone()
+            } else {
+                path[t + 1].clone()
+            };
+            let decoy = rng.pick(&path[1..]).clone();
+            let children = match directions[t % directions.len()] {
+                'L' => [next, decoy],
+                _ => [decoy, next],
+            };
+            nodes.push((path[t].clone(), children));
+        }
+
+        ghosts.push((length as u64, positions));
+    }
+
+    rng.shuffle(&mut nodes);
+
+    let mut input: String = directions.iter().collect();
+    input.push_str("\n\n");
+    for (name, [left, right]) in nodes {
+        writeln!(input, "{} = ({}, {})", name, left, right).unwrap();
+    }
+
+    // Ghosts are all in their cycles after the tail and their goals repeat
+    // from then on, so the first step at which they are all on one is within
+    // a common period of the cycles, trying the goals of the longest cycle
+    // one after another. There's always one, since the last goals of every
+    // cycle line up at the end of the period.
+    let period = ghosts.iter().fold(1, |acc, (l, _)| lcm(acc, *l as u128)) as u64;
+    let (longest, positions) = ghosts.iter().max_by_key(|(l, _)| *l).unwrap();
+    let part2 = (0..period / longest)
+        .flat_map(|k| positions.iter().map(move |p| k * longest + p))
+        .find(|steps| {
+            ghosts
+                .iter()
+                .all(|(l, positions)| positions.contains(&((steps - 1) % l + 1)))
+        })
+        .unwrap();
+
+    Generated::new(
+        input,
+        options.tail + ghosts[0].1[0],
+        Some(options.tail + part2),
+    )
+}
+
+/// Histories that are values of random polynomials of degree up to 5.
+fn day9(rng: &mut Rng, histories: usize) -> Generated {
+    const LEN: i64 = 21;
+
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+
+    for _ in 0..histories {
+        let coefficients: Vec<i64> = (0..=rng.between(0, 5))
+            .map(|k| {
+                if k == 0 {
+                    rng.between(-20, 20)
+                } else {
+                    rng.between(-3, 3)
+                }
+            })
+            .collect();
+        let at = |x: i64| coefficients.iter().rev().fold(0, |acc, c| acc * x + c);
+
+        let values: Vec<String> = (0..LEN).map(|x| at(x).to_string()).collect();
+        writeln!(input, "{}", values.join(" ")).unwrap();
+
+        part1 += at(LEN);
+        part2 += at(-1);
+    }
+
+    Generated::new(input, part1, Some(part2))
+}
+
+/// A loop of `length` pipes around a random blob of cells, grown one cell at
+/// a time without ever making holes or cells touching only by a corner, so
+/// its outline is a simple loop through the corners of the cells. Everything
+/// off the loop is junk.
+///
+/// Part 2 comes from Pick's theorem: the tiles inside are the cells in the
+/// blob minus half the loop plus one.
+fn day10(rng: &mut Rng, length: usize) -> Generated {
+    const STEPS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    // Clockwise around a cell, starting up.
+    const RING: [(i64, i64); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+
+    let length = length.max(4).next_multiple_of(2);
+    let mut blob: HashSet<(i64, i64)> = HashSet::from([(0, 0)]);
+    let mut cells = vec![(0, 0)];
+    let mut perimeter = 4;
+
+    while perimeter < length {
+        let (x, y) = *rng.pick(&cells);
+        let (dx, dy) = *rng.pick(&STEPS);
+        let cell = (x + dx, y + dy);
+        if blob.contains(&cell) {
+            continue;
+        }
+
+        let inside = |(dx, dy): (i64, i64)| blob.contains(&(cell.0 + dx, cell.1 + dy));
+        let neighbours = STEPS.iter().filter(|s| inside(**s)).count();
+
+        // Outside cells around this one must stay in a single run, otherwise
+        // adding it would enclose some of them or leave a corner touch.
+        let runs = (0..8)
+            .filter(|i| !inside(RING[*i]) && inside(RING[(i + 7) % 8]))
+            .count();
+
+        // Mostly grow the loop, but fill in some corners to get fat shapes.
+        let wanted = neighbours == 1 || (neighbours == 2 && rng.one_in(3));
+        if runs == 1 && wanted {
+            perimeter = perimeter + 4 - 2 * neighbours;
+            blob.insert(cell);
+            cells.push(cell);
+        }
+    }
+
+    // Corners of the cells, with the directions the outline leaves them in.
+    let mut corners: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+    for &(x, y) in cells.iter() {
+        // Each side as its two corners, if the cell on the other side of it
+        // is outside.
+        let sides = [
+            ((0, -1), (x, y), (x + 1, y)),
+            ((1, 0), (x + 1, y), (x + 1, y + 1)),
+            ((0, 1), (x, y + 1), (x + 1, y + 1)),
+            ((-1, 0), (x, y), (x, y + 1)),
+        ];
+
+        for ((dx, dy), a, b) in sides {
+            if !blob.contains(&(x + dx, y + dy)) {
+                corners.entry(a).or_default().push((b.0 - a.0, b.1 - a.1));
+                corners.entry(b).or_default().push((a.0 - b.0, a.1 - b.1));
+            }
+        }
+    }
+
+    let min_x = corners.keys().map(|c| c.0).min().unwrap() - 1;
+    let max_x = corners.keys().map(|c| c.0).max().unwrap() + 1;
+    let min_y = corners.keys().map(|c| c.1).min().unwrap() - 1;
+    let max_y = corners.keys().map(|c| c.1).max().unwrap() + 1;
+
+    let mut loop_tiles: Vec<(i64, i64)> = corners.keys().copied().collect();
+    loop_tiles.sort();
+    let start = *rng.pick(&loop_tiles);
+
+    let pipe = |directions: &[(i64, i64)]| {
+        let has = |d| directions.contains(&d);
+        match (has((0, -1)), has((1, 0)), has((0, 1)), has((-1, 0))) {
+            (true, _, true, _) => '|',
+            (_, true, _, true) => '-',
+            (true, true, _, _) => 'L',
+            (true, _, _, true) => 'J',
+            (_, _, true, true) => '7',
+            _ => 'F',
+        }
+    };
+
+    let mut input = String::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let next_to_start = (x - start.0).abs() + (y - start.1).abs() == 1;
+            let tile = match corners.get(&(x, y)) {
+                _ if (x, y) == start => 'S',
+                Some(directions) => pipe(directions),
+                // Junk that points at S would make its pipe ambiguous.
+                None if next_to_start => '.',
+                None => *rng.pick(&['.', '.', '.', '|', '-', 'L', 'J', '7', 'F']),
+            };
+            input.push(tile);
+        }
+        input.push('\n');
+    }
+
+    let enclosed = cells.len() as i64 - length as i64 / 2 + 1;
+    Generated::new(input, length / 2, Some(enclosed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d1::{self, MissingDigits, Scanner};
+    use crate::d10::{self, Maze};
+    use crate::d7::{self, Rules};
+    use crate::d8::{Network, NodePattern};
+    use crate::d9::Oasis;
+
+    fn answer(generated: &Generated, part: usize) -> String {
+        generated.answers[part - 1].clone().unwrap()
+    }
+
+    #[test]
+    fn test_is_deterministic() {
+        for day in DAYS {
+            let a = generate(day, &Options::sized(11, 30)).unwrap();
+            let b = generate(day, &Options::sized(11, 30)).unwrap();
+            let c = generate(day, &Options::sized(12, 30)).unwrap();
+
+            assert_eq!(a, b, "day {}", day);
+            assert_ne!(a.input, c.input, "day {}", day);
+        }
+
+        assert_eq!(generate(11, &Options::sized(1, 10)), None);
+    }
+
+    #[test]
+    fn test_day1_answers() {
+        for seed in 0..5 {
+            let generated = generate(1, &Options::sized(seed, 200)).unwrap();
+            let sum = |scanner: Scanner| {
+                d1::calibrate(generated.input.as_bytes(), MissingDigits::Error, |l| {
+                    scanner.scan(l).map(|c| c.value())
+                })
+                .unwrap()
+                .sum
+                .to_string()
+            };
+
+            assert_eq!(sum(Scanner::digits()), answer(&generated, 1));
+            assert_eq!(sum(Scanner::spelled()), answer(&generated, 2));
+        }
+    }
+
+    #[test]
+    fn test_day7_answers() {
+        let generated = generate(7, &Options::sized(3, 500)).unwrap();
+        let total = |rules| d7::solve(generated.input.as_bytes(), &rules).unwrap();
+
+        assert_eq!(total(Rules::standard()).to_string(), answer(&generated, 1));
+        assert_eq!(total(Rules::jokers()).to_string(), answer(&generated, 2));
+    }
+
+    #[test]
+    fn test_day8_answers() {
+        let generated = generate(
+            8,
+            &Options {
+                seed: 5,
+                size: Some(7),
+                cycles: vec![3, 4, 5],
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        let network = Network::parse(&generated.input).unwrap();
+
+        let walks = network.walk(|id| id == "AAA", |id| id == "ZZZ", 1_000_000);
+        assert_eq!(walks.len(), 1);
+        assert_eq!(
+            walks[0].1.as_ref().unwrap().to_string(),
+            answer(&generated, 1)
+        );
+        assert_eq!(answer(&generated, 2), "420");
+
+        let starts: Vec<u32> = network.find_all(&NodePattern::parse("*A")).collect();
+        let ends = network.find_all(&NodePattern::parse("*Z")).count();
+        assert_eq!((starts.len(), ends), (3, 3));
+    }
+
+    #[test]
+    fn test_day8_tails_and_goals() {
+        let generated = generate(
+            8,
+            &Options {
+                seed: 2,
+                size: Some(4),
+                cycles: vec![1, 2],
+                tail: 3,
+                goal_offset: 1,
+                goals: 2,
+            },
+        )
+        .unwrap();
+        let network = Network::parse(&generated.input).unwrap();
+
+        // Cycles of 4 and 8 steps after 3 steps of tail, with goals 2 steps
+        // apart for the first ghost and 4 for the second, the last a step
+        // before the end: ZZZ at 4, 6, 8... and the other ghost at 6, 10...
+        assert_eq!(network.len(), (1 + 3 + 4) + (1 + 3 + 8));
+        assert_eq!(network.find_all(&NodePattern::parse("*Z")).count(), 4);
+        assert_eq!(answer(&generated, 1), "4");
+        assert_eq!(answer(&generated, 2), "6");
+    }
+
+    #[test]
+    fn test_day9_answers() {
+        let generated = generate(9, &Options::sized(9, 50)).unwrap();
+        let sum = |steps| {
+            generated
+                .input
+                .lines()
+                .map(|l| {
+                    let history: Vec<i64> =
+                        l.split_whitespace().map(|n| n.parse().unwrap()).collect();
+                    Oasis::new(&history).unwrap().extrapolate(steps).unwrap()
+                })
+                .sum::<i64>()
+                .to_string()
+        };
+
+        assert_eq!(sum(1), answer(&generated, 1));
+        assert_eq!(sum(-1), answer(&generated, 2));
+    }
+
+    #[test]
+    fn test_day10_answers() {
+        for (seed, length) in [(1, 4), (2, 30), (3, 501), (4, 800)] {
+            let generated = generate(10, &Options::sized(seed, length)).unwrap();
+            let maze = Maze::parse(&generated.input).unwrap();
+            let path = maze.main_loop().unwrap();
+
+            assert_eq!(path.len(), length.next_multiple_of(2));
+            assert_eq!(
+                maze.farthest().unwrap().1.to_string(),
+                answer(&generated, 1)
+            );
+            assert_eq!(
+                d10::enclosed_by_area(&path).to_string(),
+                answer(&generated, 2)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "day 9 part 1, seed 0 size 10")]
+    fn test_check_answers() {
+        check_answers(9, 1, &[10], |_| 0);
+    }
+
+    #[test]
+    fn test_options_from_args() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            Options::from_args(args(&["generate", "8"])),
+            Options::default()
+        );
+        assert_eq!(
+            Options::from_args(args(&[
+                "generate",
+                "8",
+                "--seed=3",
+                "--size=9",
+                "--cycles=2,7",
+                "--tail=4",
+                "--goal-offset=1",
+                "--goals=3"
+            ])),
+            Options {
+                seed: 3,
+                size: Some(9),
+                cycles: vec![2, 7],
+                tail: 4,
+                goal_offset: 1,
+                goals: 3,
+            }
+        );
+    }
+}
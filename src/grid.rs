@@ -0,0 +1,297 @@
+//! Image export for grid based puzzles, as PPM bitmaps or SVG files.
+//!
+//! Every day maps its cells to its own class type and picks a [`Palette`]
+//! for it, the drawing itself is shared.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io;
+use std::path::PathBuf;
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Clone)]
+pub struct Palette<C> {
+    background: Rgb,
+    colors: HashMap<C, Rgb>,
+}
+
+impl<C: Copy + Eq + Hash> Palette<C> {
+    pub fn new(background: Rgb) -> Self {
+        Self {
+            background,
+            colors: HashMap::new(),
+        }
+    }
+
+    pub fn with(mut self, class: C, color: Rgb) -> Self {
+        self.colors.insert(class, color);
+        self
+    }
+
+    /// Colour of a cell, the background for empty cells or classes without
+    /// a colour.
+    pub fn color(&self, class: Option<C>) -> Rgb {
+        class
+            .and_then(|c| self.colors.get(&c))
+            .copied()
+            .unwrap_or(self.background)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<C> {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<C>>,
+}
+
+impl<C: Copy + Eq + Hash> Grid<C> {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![None; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, class: C) {
+        assert!(x < self.width && y < self.height, "cell out of the grid");
+        self.cells[y * self.width + x] = Some(class);
+    }
+
+    /// The class of a cell, `None` for empty cells and cells out of the
+    /// grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<C> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.cells[y * self.width + x]
+    }
+
+    /// Binary (P6) PPM where every cell is a `cell_size` square.
+    pub fn to_ppm(&self, palette: &Palette<C>, cell_size: usize) -> Vec<u8> {
+        let width = self.width * cell_size;
+        let height = self.height * cell_size;
+
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        ppm.reserve(width * height * 3);
+
+        for py in 0..height {
+            for px in 0..width {
+                let class = self.get(px / cell_size, py / cell_size);
+                ppm.extend_from_slice(&palette.color(class));
+            }
+        }
+
+        ppm
+    }
+
+    /// SVG with a background and a `cell_size` square for every non-empty
+    /// cell.
+    pub fn to_svg(&self, palette: &Palette<C>, cell_size: usize) -> String {
+        let hex = |[r, g, b]: Rgb| format!("#{:02x}{:02x}{:02x}", r, g, b);
+        let width = self.width * cell_size;
+        let height = self.height * cell_size;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+            width, height
+        );
+        svg.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            width,
+            height,
+            hex(palette.background)
+        ));
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some(class) = self.get(x, y) {
+                    svg.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                        x * cell_size,
+                        y * cell_size,
+                        cell_size,
+                        cell_size,
+                        hex(palette.color(Some(class)))
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Svg,
+}
+
+/// Where and how to save an image, read from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageExport {
+    pub path: PathBuf,
+    pub format: ImageFormat,
+    pub cell_size: usize,
+}
+
+impl ImageExport {
+    const DEFAULT_CELL_SIZE: usize = 8;
+
+    /// Looks for `--image=<path>` and optionally `--cell-size=<n>`, with `n`
+    /// above 0, in the arguments. The format is picked from the extension of
+    /// the path, `.svg` or anything else for PPM.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut path = None;
+        let mut cell_size = Self::DEFAULT_CELL_SIZE;
+
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--image=") {
+                path = Some(PathBuf::from(value));
+            } else if let Some(value) = arg.strip_prefix("--cell-size=") {
+                cell_size = value
+                    .parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .expect("cell size is not a positive number");
+            }
+        }
+
+        let path = path?;
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => ImageFormat::Svg,
+            _ => ImageFormat::Ppm,
+        };
+
+        Some(Self {
+            path,
+            format,
+            cell_size,
+        })
+    }
+
+    pub fn save<C: Copy + Eq + Hash>(
+        &self,
+        grid: &Grid<C>,
+        palette: &Palette<C>,
+    ) -> io::Result<()> {
+        let bytes = match self.format {
+            ImageFormat::Ppm => grid.to_ppm(palette, self.cell_size),
+            ImageFormat::Svg => grid.to_svg(palette, self.cell_size).into_bytes(),
+        };
+
+        std::fs::write(&self.path, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Class {
+        A,
+        B,
+    }
+
+    fn sample() -> (Grid<Class>, Palette<Class>) {
+        let mut grid = Grid::new(2, 1);
+        grid.set(0, 0, Class::A);
+
+        let palette = Palette::new([0, 0, 0]).with(Class::A, [255, 0, 16]);
+
+        (grid, palette)
+    }
+
+    #[test]
+    fn test_get() {
+        let (grid, _) = sample();
+        assert_eq!(grid.get(0, 0), Some(Class::A));
+        assert_eq!(grid.get(1, 0), None);
+        // Past the end of the row, not the start of the next one.
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 1), None);
+    }
+
+    #[test]
+    fn test_palette() {
+        let (_, palette) = sample();
+        assert_eq!(palette.color(Some(Class::A)), [255, 0, 16]);
+        assert_eq!(palette.color(Some(Class::B)), [0, 0, 0]);
+        assert_eq!(palette.color(None), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let (grid, palette) = sample();
+        let ppm = grid.to_ppm(&palette, 2);
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(&pixels[..12], &[255, 0, 16, 255, 0, 16, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let (grid, palette) = sample();
+
+        assert_eq!(
+            grid.to_svg(&palette, 3),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"6\" height=\"3\">
+<rect width=\"6\" height=\"3\" fill=\"#000000\"/>
+<rect x=\"0\" y=\"0\" width=\"3\" height=\"3\" fill=\"#ff0010\"/>
+</svg>
+"
+        );
+    }
+
+    #[test]
+    fn test_image_export_from_args() {
+        let args = |a: &[&str]| {
+            a.iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        assert_eq!(ImageExport::from_args(args(&["d10p2"])), None);
+        assert_eq!(
+            ImageExport::from_args(args(&["d10p2", "--image=maze.svg", "--cell-size=4"])),
+            Some(ImageExport {
+                path: PathBuf::from("maze.svg"),
+                format: ImageFormat::Svg,
+                cell_size: 4,
+            })
+        );
+        assert_eq!(
+            ImageExport::from_args(args(&["d3p1", "--image=engine.ppm"])).map(|e| e.format),
+            Some(ImageFormat::Ppm)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cell size is not a positive number")]
+    fn test_zero_cell_size() {
+        ImageExport::from_args(
+            ["d3p1", "--image=engine.ppm", "--cell-size=0"]
+                .map(String::from)
+                .into_iter(),
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Code shared between the solutions in `src/bin`.
+
+pub mod d1;
+pub mod d10;
+pub mod d3;
+pub mod d7;
+pub mod d8;
+pub mod d9;
+pub mod differential;
+pub mod fixtures;
+pub mod generate;
+pub mod grid;
+pub mod lint;
+pub mod math;
+pub mod parse;
+pub mod reader;
+pub mod report;
@@ -0,0 +1,721 @@
+//! Checks of what the solutions assume about their input.
+//!
+//! The puzzles describe their inputs in prose, and the solutions rely on it
+//! without checking much: card ids go up one by one, hands have 5 cards,
+//! there's a single `S` in the maze. [`lint`] checks those invariants and
+//! reports every line that breaks one, without solving anything.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::d10::Tile;
+use crate::parse::{self, ParseError, Span};
+
+/// Every violation of the invariants of `day` in `puzzle`, in input order,
+/// or `None` if there are no checks for that day.
+pub fn lint(day: u8, puzzle: &str) -> Option<Vec<ParseError>> {
+    let puzzle = Span::new(puzzle);
+    if puzzle.as_str().trim().is_empty() {
+        return Some(vec![puzzle.error("empty input")]);
+    }
+
+    let mut errors = match day {
+        1 => day1(puzzle),
+        2 => day2(puzzle),
+        3 => grid(puzzle, |c| {
+            c == '.' || c.is_ascii_digit() || c.is_ascii_punctuation()
+        }),
+        4 => day4(puzzle),
+        5 => day5(puzzle),
+        6 => day6(puzzle),
+        7 => day7(puzzle),
+        8 => day8(puzzle),
+        9 => day9(puzzle),
+        10 => day10(puzzle),
+        _ => return None,
+    };
+
+    errors.sort_by_key(|e| (e.line, e.column));
+    Some(errors)
+}
+
+/// The span of the char at byte `index` of `span`.
+fn char_at(span: Span, index: usize) -> Span {
+    let len = span.as_str()[index..]
+        .chars()
+        .next()
+        .map_or(0, char::len_utf8);
+    span.slice(&span.as_str()[index..index + len])
+}
+
+/// The first char of `span` that isn't `allowed`, as an error.
+fn unexpected_char(span: Span, allowed: impl Fn(char) -> bool) -> Option<ParseError> {
+    span.as_str()
+        .char_indices()
+        .find(|(_, c)| !allowed(*c))
+        .map(|(i, c)| char_at(span, i).error(format!("unexpected {:?}", c)))
+}
+
+/// Lines of a line oriented puzzle, reporting empty ones.
+fn lines<'a>(puzzle: Span<'a>, errors: &mut Vec<ParseError>) -> Vec<Span<'a>> {
+    puzzle
+        .lines()
+        .filter(|line| {
+            let empty = line.as_str().trim().is_empty();
+            if empty {
+                errors.push(line.error("empty line"));
+            }
+            !empty
+        })
+        .collect()
+}
+
+fn day1(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+
+    for line in lines(puzzle, &mut errors) {
+        errors.extend(unexpected_char(line, |c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit()
+        }));
+
+        if !line.as_str().chars().any(|c| c.is_ascii_digit()) {
+            errors.push(line.error("no digits"));
+        }
+    }
+
+    errors
+}
+
+fn day2(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+
+    for (expected, line) in (1..).zip(lines(puzzle, &mut errors)) {
+        let (id, sets) = match parse::labelled::<u32>(line, "Game") {
+            Ok(game) => game,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        if id != expected {
+            errors.push(line.error(format!("game {} where game {} was expected", id, expected)));
+        }
+
+        for set in sets.split(';') {
+            let mut seen = HashSet::new();
+
+            for cubes in set.split(',') {
+                let (count, color) = match cubes.trim().split_once(" ") {
+                    Ok(cubes) => cubes,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
+
+                match count.parse::<u32>() {
+                    Ok(0) => errors.push(count.error("no cubes")),
+                    Ok(_) => {}
+                    Err(e) => errors.push(e),
+                }
+
+                if !["red", "green", "blue"].contains(&color.as_str()) {
+                    errors.push(color.error(format!("unknown color {:?}", color.as_str())));
+                } else if !seen.insert(color.as_str()) {
+                    errors.push(color.error("color shown twice in the same set"));
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// A rectangular grid of `allowed` chars.
+fn grid(puzzle: Span, allowed: impl Fn(char) -> bool) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let rows = lines(puzzle, &mut errors);
+    let width = rows[0].as_str().chars().count();
+
+    for row in rows {
+        let len = row.as_str().chars().count();
+        if len != width {
+            errors.push(row.error(format!("{} columns where the first row has {}", len, width)));
+        }
+
+        errors.extend(unexpected_char(row, &allowed));
+    }
+
+    errors
+}
+
+/// The numbers of `list`, reporting the repeated ones.
+fn duplicates<'a>(list: Span<'a>, errors: &mut Vec<ParseError>) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    let mut numbers = Vec::new();
+
+    for n in list.split_whitespace() {
+        match n.parse() {
+            Ok(value) => {
+                if !seen.insert(value) {
+                    errors.push(n.error(format!("{} is repeated", value)));
+                }
+                numbers.push(value);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    numbers
+}
+
+fn day4(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let mut sizes = None;
+    let mut matches = Vec::new();
+
+    for (expected, line) in (1..).zip(lines(puzzle, &mut errors)) {
+        let card = parse::labelled::<u32>(line, "Card")
+            .and_then(|(id, numbers)| Ok((id, numbers.split_once("|")?)));
+        let (id, (winning, own)) = match card {
+            Ok(card) => card,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        if id != expected {
+            errors.push(line.error(format!("card {} where card {} was expected", id, expected)));
+        }
+
+        let winning = duplicates(winning, &mut errors);
+        let own = duplicates(own, &mut errors);
+
+        match sizes {
+            None => sizes = Some((winning.len(), own.len())),
+            Some(first) if first != (winning.len(), own.len()) => errors.push(line.error(format!(
+                "{} winning and {} own numbers where the first card has {} and {}",
+                winning.len(),
+                own.len(),
+                first.0,
+                first.1
+            ))),
+            Some(_) => {}
+        }
+
+        matches.push((line, winning.iter().filter(|n| own.contains(n)).count()));
+    }
+
+    let cards = matches.len();
+    for (i, (line, count)) in matches.into_iter().enumerate() {
+        if i + count >= cards {
+            errors.push(line.error(format!(
+                "{} matches would copy cards past the end of the table",
+                count
+            )));
+        }
+    }
+
+    errors
+}
+
+fn day5(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let sections = parse::sections(puzzle);
+
+    match parse::key_value(sections[0]) {
+        Ok((key, _)) if key.as_str() != "seeds" => errors.push(key.error("expected \"seeds\"")),
+        Ok((_, seeds)) => match parse::integers::<u64>(seeds) {
+            Ok(seeds) if seeds.len() % 2 == 1 => {
+                errors.push(sections[0].error("odd number of seeds, part 2 needs pairs"))
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        },
+        Err(e) => errors.push(e),
+    }
+
+    let mut category = "seed";
+
+    for section in sections[1..].iter() {
+        let mut lines = section.lines();
+        let header = lines.next().unwrap();
+
+        let name = header
+            .as_str()
+            .strip_suffix(" map:")
+            .and_then(|name| name.split_once("-to-"));
+        match name {
+            Some((from, to)) => {
+                if from != category {
+                    errors.push(header.error(format!(
+                        "map from {} where one from {} was expected",
+                        from, category
+                    )));
+                }
+                category = to;
+            }
+            None => errors.push(header.error("expected \"<from>-to-<to> map:\"")),
+        }
+
+        let mut ranges = Vec::new();
+        for line in lines {
+            match parse::integers::<u64>(line).as_deref() {
+                Ok([_, _, 0]) => errors.push(line.error("empty range")),
+                Ok([_, start, len]) => match start.checked_add(*len) {
+                    Some(end) => ranges.push((*start, end, line)),
+                    None => errors.push(line.error("source range ends past the largest number")),
+                },
+                Ok(_) => errors.push(line.error("expected 3 numbers")),
+                Err(e) => errors.push(e.clone()),
+            }
+        }
+
+        ranges.sort_by_key(|(start, _, _)| *start);
+        for pair in ranges.windows(2) {
+            let ((_, end, first), (start, _, second)) = (pair[0], pair[1]);
+            if start < end {
+                errors.push(second.error(format!(
+                    "source range overlaps the one on line {}",
+                    first.line()
+                )));
+            }
+        }
+    }
+
+    if category != "location" {
+        let last = sections.last().unwrap();
+        errors.push(last.error(format!("maps end at {} instead of location", category)));
+    }
+
+    errors
+}
+
+fn day6(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let rows = lines(puzzle, &mut errors);
+
+    let mut numbers = |index: usize, name: &str| {
+        let Some(row) = rows.get(index) else {
+            errors.push(puzzle.error(format!("no {} line", name)));
+            return Vec::new();
+        };
+
+        let parsed = parse::key_value(*row).and_then(|(key, value)| {
+            if key.as_str() != name {
+                return Err(key.error(format!("expected {:?}", name)));
+            }
+
+            value
+                .split_whitespace()
+                .map(|n| Ok((n, n.parse::<u64>()?)))
+                .collect::<Result<Vec<_>, ParseError>>()
+        });
+
+        parsed.unwrap_or_else(|e| {
+            errors.push(e);
+            Vec::new()
+        })
+    };
+
+    let times = numbers(0, "Time");
+    let records = numbers(1, "Distance");
+
+    if times.len() != records.len() {
+        errors.push(rows[rows.len().min(2) - 1].error(format!(
+            "{} records for {} races",
+            records.len(),
+            times.len()
+        )));
+    }
+
+    for ((time_span, time), (span, record)) in times.iter().zip(records.iter()) {
+        let Some(best) = (time / 2).checked_mul(time - time / 2) else {
+            errors.push(time_span.error(format!(
+                "the best distance in a {} ms race is past the largest number",
+                time
+            )));
+            continue;
+        };
+
+        if best <= *record {
+            errors.push(span.error(format!("record can not be beaten in a {} ms race", time)));
+        }
+    }
+
+    errors
+}
+
+fn day7(puzzle: Span) -> Vec<ParseError> {
+    const LABELS: &str = "23456789TJQKA";
+
+    let mut errors = Vec::new();
+    let mut hands: HashMap<&str, usize> = HashMap::new();
+
+    for line in lines(puzzle, &mut errors) {
+        let (hand, bid) = match line.split_once(" ") {
+            Ok(play) => play,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let cards = hand.as_str().chars().count();
+        if cards != 5 {
+            errors.push(hand.error(format!("{} cards instead of 5", cards)));
+        }
+        errors.extend(unexpected_char(hand, |c| LABELS.contains(c)));
+
+        if let Some(first) = hands.insert(hand.as_str(), line.line()) {
+            errors.push(hand.error(format!("same hand as line {}", first)));
+        }
+
+        match bid.parse::<u32>() {
+            Ok(0) => errors.push(bid.error("bid of 0")),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    errors
+}
+
+fn day8(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let mut lines = puzzle.lines();
+
+    let directions = lines.next().unwrap();
+    errors.extend(unexpected_char(directions, |c| c == 'L' || c == 'R'));
+
+    match lines.next() {
+        Some(line) if !line.is_empty() => errors.push(line.error("expected an empty line")),
+        Some(_) => {}
+        None => errors.push(directions.error("no nodes")),
+    }
+
+    let mut check_name = |name: Span| {
+        let valid = name.as_str().len() == 3
+            && name
+                .as_str()
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+        if !valid {
+            errors.push(name.error(format!(
+                "node {:?} is not 3 letters or digits",
+                name.as_str()
+            )));
+        }
+    };
+
+    let mut defined: HashMap<&str, usize> = HashMap::new();
+    let mut children = Vec::new();
+    let mut syntax = Vec::new();
+
+    for line in lines {
+        let node = line
+            .split_once(" = ")
+            .and_then(|(name, to)| Ok((name, parse::tuple(to)?)));
+
+        match node {
+            Ok((name, to)) if to.len() == 2 => {
+                check_name(name);
+                to.iter().for_each(|child| check_name(*child));
+
+                if let Some(first) = defined.insert(name.as_str(), line.line()) {
+                    syntax.push(name.error(format!("node already defined on line {}", first)));
+                }
+                children.extend(to);
+            }
+            Ok((_, to)) => syntax.push(line.error(format!("{} children instead of 2", to.len()))),
+            Err(e) => syntax.push(e),
+        }
+    }
+
+    errors.extend(syntax);
+
+    for child in children {
+        if !defined.contains_key(child.as_str()) {
+            errors.push(child.error(format!("node {} is not defined", child.as_str())));
+        }
+    }
+
+    for name in ["AAA", "ZZZ"] {
+        if !defined.contains_key(name) {
+            errors.push(directions.error(format!("no {} node", name)));
+        }
+    }
+
+    errors
+}
+
+fn day9(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let mut len = None;
+
+    for line in lines(puzzle, &mut errors) {
+        match parse::integers::<i64>(line) {
+            Ok(values) => match len {
+                None => len = Some(values.len()),
+                Some(first) if first != values.len() => errors.push(line.error(format!(
+                    "{} values where the first history has {}",
+                    values.len(),
+                    first
+                ))),
+                Some(_) => {}
+            },
+            Err(e) => errors.push(e),
+        }
+    }
+
+    errors
+}
+
+fn day10(puzzle: Span) -> Vec<ParseError> {
+    let mut errors = grid(puzzle, |c| "|-LJ7F.S".contains(c));
+
+    let rows: Vec<Span> = puzzle
+        .lines()
+        .filter(|line| !line.as_str().trim().is_empty())
+        .collect();
+    let tile = |x: i64, y: i64| {
+        let row = rows.get(usize::try_from(y).ok()?)?;
+        let c = row.as_str().chars().nth(usize::try_from(x).ok()?)?;
+        Some(Tile::from(c, x, y))
+    };
+
+    let starts: Vec<(i64, i64, Span)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.as_str()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'S')
+                .map(move |(x, _)| (x as i64, y as i64, *row))
+        })
+        .map(|(x, y, row)| {
+            let index = row.as_str().char_indices().nth(x as usize).unwrap().0;
+            (x, y, char_at(row, index))
+        })
+        .collect();
+
+    let Some((x, y, first)) = starts.first().copied() else {
+        errors.push(puzzle.error("no starting tile S"));
+        return errors;
+    };
+
+    for (_, _, other) in starts.iter().skip(1) {
+        errors.push(other.error(format!(
+            "another S, the first is at line {}, column {}",
+            first.line(),
+            first.column()
+        )));
+    }
+
+    let start = Tile::from('S', x, y).absolute_pos;
+    let connected = start
+        .neighbors()
+        .iter()
+        .filter_map(|p| tile(p.x, p.y))
+        .filter(|t| t.connections.contains(&start))
+        .count();
+
+    if connected != 2 {
+        errors.push(first.error(format!("{} pipes connect to S instead of 2", connected)));
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::generate::{self, Options, DAYS};
+
+    fn messages(day: u8, puzzle: &str) -> Vec<String> {
+        lint(day, puzzle)
+            .unwrap()
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_generated_inputs_are_clean() {
+        for day in DAYS {
+            for seed in 0..5 {
+                let generated = generate::generate(day, &Options::sized(seed, 20)).unwrap();
+
+                assert_eq!(
+                    messages(day, &generated.input),
+                    Vec::<String>::new(),
+                    "day {} seed {}",
+                    day,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples_are_clean() {
+        for day in DAYS {
+            let example = fixtures::read(day, "example");
+            assert_eq!(messages(day, &example), Vec::<String>::new(), "day {}", day);
+        }
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert_eq!(lint(11, "x"), None);
+        assert_eq!(messages(1, "\n \n"), ["line 1, column 1: empty input"]);
+    }
+
+    #[test]
+    fn test_day4_card_ids() {
+        let puzzle = "Card 1: 1 2 | 1 3
+Card 3: 4 5 | 6 6
+Card  3: 4 5 6 | 7 8
+";
+
+        assert_eq!(
+            messages(4, puzzle),
+            [
+                "line 2, column 1: card 3 where card 2 was expected",
+                "line 2, column 17: 6 is repeated",
+                "line 3, column 1: 3 winning and 2 own numbers where the first card has 2 and 2",
+            ]
+        );
+
+        assert_eq!(
+            messages(4, "Card 1: 1 2 | 1 2\nCard 2: 1 2 | 3 4\n"),
+            ["line 1, column 1: 2 matches would copy cards past the end of the table"]
+        );
+    }
+
+    #[test]
+    fn test_day7_hands() {
+        let puzzle = "32T3K 765
+T55J 684
+
+KK67X 28
+32T3K 1
+";
+
+        assert_eq!(
+            messages(7, puzzle),
+            [
+                "line 2, column 1: 4 cards instead of 5",
+                "line 3, column 1: empty line",
+                "line 4, column 5: unexpected 'X'",
+                "line 5, column 1: same hand as line 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day8_nodes() {
+        let puzzle = "LRX
+
+AAA = (BB, ZZZ)
+ZZZ = (ZZZ, CCC)
+AAA = (ZZZ, ZZZ)
+";
+
+        assert_eq!(
+            messages(8, puzzle),
+            [
+                "line 1, column 3: unexpected 'X'",
+                "line 3, column 8: node \"BB\" is not 3 letters or digits",
+                "line 3, column 8: node BB is not defined",
+                "line 4, column 13: node CCC is not defined",
+                "line 5, column 1: node already defined on line 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day10_start() {
+        assert_eq!(
+            messages(10, "F-7\n|.|\nL-J\n"),
+            ["line 1, column 1: no starting tile S"]
+        );
+
+        assert_eq!(
+            messages(10, "S-7\n|.|\nL-S\n"),
+            ["line 3, column 3: another S, the first is at line 1, column 1"]
+        );
+
+        assert_eq!(
+            messages(10, ".S7\n|.|\nL-J\n"),
+            ["line 1, column 2: 1 pipes connect to S instead of 2"]
+        );
+    }
+
+    #[test]
+    fn test_other_days() {
+        assert_eq!(
+            messages(1, "ab1\nNone\n"),
+            [
+                "line 2, column 1: unexpected 'N'",
+                "line 2, column 1: no digits"
+            ]
+        );
+        assert_eq!(
+            messages(2, "Game 1: 3 blue, 4 pink; 2 red, 0 red\n"),
+            [
+                "line 1, column 19: unknown color \"pink\"",
+                "line 1, column 32: no cubes",
+                "line 1, column 34: color shown twice in the same set",
+            ]
+        );
+        assert_eq!(
+            messages(3, "467..\n..*..x\n"),
+            [
+                "line 2, column 1: 6 columns where the first row has 5",
+                "line 2, column 6: unexpected 'x'",
+            ]
+        );
+        assert_eq!(
+            messages(
+                5,
+                "seeds: 1 2 3\n\nseed-to-soil map:\n0 10 5\n0 12 1\n\nsoil-to-water map:\n1 2 0\n"
+            ),
+            [
+                "line 1, column 1: odd number of seeds, part 2 needs pairs",
+                "line 5, column 1: source range overlaps the one on line 4",
+                "line 7, column 1: maps end at water instead of location",
+                "line 8, column 1: empty range",
+            ]
+        );
+        assert_eq!(
+            messages(
+                5,
+                "seeds: 1 2\n\nseed-to-location map:\n0 18446744073709551615 1\n"
+            ),
+            ["line 4, column 1: source range ends past the largest number"]
+        );
+        assert_eq!(
+            messages(6, "Time: 7 15\nDistance: 12 40 5\n"),
+            [
+                "line 2, column 1: 3 records for 2 races",
+                "line 2, column 11: record can not be beaten in a 7 ms race",
+            ]
+        );
+        assert_eq!(
+            messages(6, "Time: 7 8589934594\nDistance: 9 40\n"),
+            ["line 1, column 9: the best distance in a 8589934594 ms race is past the largest number"]
+        );
+        assert_eq!(
+            messages(9, "0 3 6\n1 x\n1 2\n"),
+            [
+                "line 2, column 3: can not parse \"x\": invalid digit found in string",
+                "line 3, column 1: 2 values where the first history has 3",
+            ]
+        );
+    }
+}
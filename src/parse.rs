@@ -0,0 +1,301 @@
+//! Small helpers to parse the puzzle inputs.
+//!
+//! They work on [`Span`]s, slices of the input that remember the line and
+//! column they start at, so a [`ParseError`] points at the right place in
+//! the original input instead of at some rewritten copy of it.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Something unexpected in the input. Lines and columns count from 1, and
+/// columns are in chars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A slice of the input and where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Span<'a> {
+    /// The whole input.
+    pub fn new(text: &'a str) -> Self {
+        Self::at_line(text, 1)
+    }
+
+    /// A single line read on its own, like when streaming the input.
+    pub fn at_line(text: &'a str, line: usize) -> Self {
+        Self {
+            text,
+            line,
+            column: 1,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    /// Byte offset of `sub` in this span, which it must be a slice of.
+    fn offset(&self, sub: &str) -> usize {
+        (sub.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|offset| offset + sub.len() <= self.text.len())
+            .expect("not a slice of the span")
+    }
+
+    /// The span of `sub`, which must be a slice of this span's text.
+    pub fn slice(&self, sub: &'a str) -> Self {
+        let before = &self.text[..self.offset(sub)];
+
+        let (line, column) = match before.rfind('\n') {
+            Some(i) => (
+                self.line + before.matches('\n').count(),
+                before[i + 1..].chars().count() + 1,
+            ),
+            None => (self.line, self.column + before.chars().count()),
+        };
+
+        Self {
+            text: sub,
+            line,
+            column,
+        }
+    }
+
+    pub fn trim(&self) -> Self {
+        self.slice(self.text.trim())
+    }
+
+    pub fn lines(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.lines().enumerate().map(move |(i, line)| Span {
+            text: line,
+            line: self.line + i,
+            column: if i == 0 { self.column } else { 1 },
+        })
+    }
+
+    pub fn split(self, separator: char) -> impl Iterator<Item = Span<'a>> {
+        self.text.split(separator).map(move |s| self.slice(s))
+    }
+
+    pub fn split_whitespace(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.split_whitespace().map(move |s| self.slice(s))
+    }
+
+    /// Splits around the first `separator`, which must be there.
+    pub fn split_once(&self, separator: &str) -> Result<(Self, Self), ParseError> {
+        let (left, right) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("expected {:?}", separator)))?;
+
+        Ok((self.slice(left), self.slice(right)))
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Result<Self, ParseError> {
+        self.text
+            .strip_prefix(prefix)
+            .map(|rest| self.slice(rest))
+            .ok_or_else(|| self.error(format!("expected {:?}", prefix)))
+    }
+
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.text
+            .parse()
+            .map_err(|e| self.error(format!("can not parse {:?}: {}", self.text, e)))
+    }
+}
+
+/// Groups lines into sections separated by blank lines, ignoring the blank
+/// lines themselves.
+pub fn sections(input: Span) -> Vec<Span> {
+    let mut sections = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+
+    for line in input.lines() {
+        if line.as_str().trim().is_empty() {
+            sections.extend(current.take());
+            continue;
+        }
+
+        let start = input.offset(line.as_str());
+        let end = start + line.as_str().len();
+        current = Some(current.map_or((start, end), |(start, _)| (start, end)));
+    }
+
+    sections.extend(current);
+    sections
+        .into_iter()
+        .map(|(start, end)| input.slice(&input.as_str()[start..end]))
+        .collect()
+}
+
+/// Splits a `key: value` line, trimming both sides.
+pub fn key_value(line: Span) -> Result<(Span, Span), ParseError> {
+    let (key, value) = line.split_once(":")?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Whitespace separated integers, optionally signed like `-3` or `+3`.
+pub fn integers<T>(span: Span) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    span.split_whitespace().map(|n| n.parse()).collect()
+}
+
+/// Strips a `label N:` prefix, like `Card  12:`, returning `N` and the
+/// trimmed rest of the line.
+pub fn labelled<'a, T>(line: Span<'a>, label: &str) -> Result<(T, Span<'a>), ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let rest = line.strip_prefix(label)?;
+    let (n, rest) = key_value(rest)?;
+
+    Ok((n.parse()?, rest))
+}
+
+/// The trimmed, comma separated items of a parenthesised tuple like
+/// `(BBB, CCC)`.
+pub fn tuple(span: Span) -> Result<Vec<Span>, ParseError> {
+    let span = span.trim();
+    let inner = span
+        .strip_prefix("(")?
+        .as_str()
+        .strip_suffix(')')
+        .ok_or_else(|| span.error("expected a closing \")\""))?;
+
+    Ok(span
+        .slice(inner)
+        .split(',')
+        .map(|item| item.trim())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "seeds: 79 -14 +55
+
+seed-to-soil map:
+50 98 2
+ 52 50 x
+
+";
+
+    #[test]
+    fn test_sections() {
+        let sections = sections(Span::new(INPUT));
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].as_str(), "seeds: 79 -14 +55");
+        assert_eq!(sections[1].as_str(), "seed-to-soil map:\n50 98 2\n 52 50 x");
+        assert_eq!((sections[1].line(), sections[1].column()), (3, 1));
+    }
+
+    #[test]
+    fn test_key_value_and_integers() {
+        let line = Span::new(INPUT).lines().next().unwrap();
+        let (key, value) = key_value(line).unwrap();
+
+        assert_eq!(key.as_str(), "seeds");
+        assert_eq!(value.column(), 8);
+        assert_eq!(integers::<i64>(value), Ok(vec![79, -14, 55]));
+    }
+
+    #[test]
+    fn test_errors_are_located() {
+        let maps = sections(Span::new(INPUT))[1];
+        let errors: Vec<_> = maps
+            .lines()
+            .skip(1)
+            .filter_map(|l| integers::<i64>(l).err())
+            .collect();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (5, 8));
+        assert_eq!(
+            errors[0].to_string(),
+            "line 5, column 8: can not parse \"x\": invalid digit found in string"
+        );
+
+        let missing = key_value(Span::at_line("no colon", 7)).unwrap_err();
+        assert_eq!((missing.line, missing.column), (7, 1));
+    }
+
+    #[test]
+    fn test_labelled() {
+        let (id, rest) = labelled::<u32>(Span::new("Card  12: 41 48 | 83"), "Card").unwrap();
+
+        assert_eq!(id, 12);
+        assert_eq!(rest.as_str(), "41 48 | 83");
+        assert_eq!(rest.column(), 11);
+
+        let error = labelled::<u32>(Span::new("Game 1: 3 blue"), "Card").unwrap_err();
+        assert_eq!(error.message, "expected \"Card\"");
+    }
+
+    #[test]
+    fn test_tuple() {
+        let line = Span::new("AAA = (BBB, CCC)");
+        let (_, to) = line.split_once(" = ").unwrap();
+        let items = tuple(to).unwrap();
+
+        assert_eq!(
+            items.iter().map(|i| i.as_str()).collect::<Vec<_>>(),
+            ["BBB", "CCC"]
+        );
+        assert_eq!(items[1].column(), 13);
+
+        let error = tuple(Span::new("(BBB, CCC")).unwrap_err();
+        assert_eq!(error.message, "expected a closing \")\"");
+    }
+}
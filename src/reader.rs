@@ -0,0 +1,100 @@
+//! Streaming puzzle input for the line oriented days.
+//!
+//! Solvers take any `BufRead` and go through it one line at a time, reusing
+//! the same buffer, so memory doesn't grow with the input. That allows piping
+//! huge generated inputs through stdin with `--stdin` instead of fetching the
+//! real puzzle.
+
+use std::io::{self, BufRead, Cursor};
+
+/// The puzzle to solve: stdin if `--stdin` is in the arguments, otherwise
+/// whatever `fetch` returns, usually the input from aocd.
+///
+/// `fetch` is only called when needed, so no session token is required to
+/// read from stdin.
+pub fn open(
+    mut args: impl Iterator<Item = String>,
+    fetch: impl FnOnce() -> String,
+) -> Box<dyn BufRead> {
+    if args.any(|arg| arg == "--stdin") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(Cursor::new(fetch()))
+    }
+}
+
+/// Calls `f` with every line of `reader`, without the line terminator
+/// (`\n` or `\r\n`), stopping at the first error.
+pub fn try_for_each_line<E: From<io::Error>>(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+        let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+        f(trimmed)?;
+    }
+}
+
+/// Same as [`try_for_each_line`] for callbacks that can't fail.
+pub fn for_each_line(reader: impl BufRead, mut f: impl FnMut(&str)) -> io::Result<()> {
+    try_for_each_line(reader, |line| {
+        f(line);
+        Ok::<_, io::Error>(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_match_str_lines() {
+        for text in [
+            "",
+            "\n",
+            "a",
+            "a\n",
+            "a\r\nb\n\nc",
+            "a\n\n",
+            "\r\n\r\nx\r\n",
+        ] {
+            let mut lines = Vec::new();
+            for_each_line(text.as_bytes(), |l| lines.push(l.to_string())).unwrap();
+
+            assert_eq!(lines, text.lines().collect::<Vec<_>>(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_stops_at_first_error() {
+        let mut seen = 0;
+        let result = try_for_each_line("1\nx\n3\n".as_bytes(), |l| {
+            seen += 1;
+            l.parse::<u32>()
+                .map(|_| ())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    fn test_open_without_stdin_flag_fetches() {
+        let args = ["d1p1".to_string()].into_iter();
+        let mut text = String::new();
+        open(args, || "fetched".to_string())
+            .read_line(&mut text)
+            .unwrap();
+
+        assert_eq!(text, "fetched");
+    }
+}
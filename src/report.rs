@@ -0,0 +1,329 @@
+//! How solutions print their answer.
+//!
+//! By default only the answer is printed. With `--format json` every run
+//! prints one line of JSON instead, so results of many runs can be collected
+//! as newline delimited JSON:
+//!
+//! ```text
+//! {"year":2023,"day":1,"part":2,"variant":"a1","answer":"281","parse_ns":0,"solve_ns":51200,"input_hash":"3c8e0f2d6a3e6b5f"}
+//! ```
+//!
+//! `parse_ns` is only set by solutions that parse the whole input before
+//! solving. The others parse while they solve, so it's 0 and `solve_ns`
+//! covers both. `input_hash` is the [`fingerprint`] of the input, to tell
+//! apart results of different inputs.
+
+use std::fmt::{self, Write as _};
+use std::io::{self, BufRead, Read};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Format {
+    /// Reads `--format <text|json>`, or `--format=<text|json>`, from the
+    /// arguments.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut format = None;
+
+        while let Some(arg) = args.next() {
+            if arg == "--format" {
+                format = args.next();
+            } else if let Some(value) = arg.strip_prefix("--format=") {
+                format = Some(value.to_string());
+            }
+        }
+
+        match format.as_deref() {
+            None | Some("text") => Self::Text,
+            Some("json") => Self::Json,
+            Some(other) => panic!("unknown format {:?}", other),
+        }
+    }
+}
+
+/// Which solution ran, from the name of its binary: `d1p2a1` is the first
+/// alternative of day 1 part 2, and `d1p2` its main solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+}
+
+impl Solution {
+    pub fn from_bin_name(name: &str) -> Option<Self> {
+        let rest = name.strip_prefix('d')?;
+        let (day, rest) = rest.split_once('p')?;
+        let part_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (part, variant) = rest.split_at(part_len);
+
+        Some(Self {
+            year: 2023,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            variant: if variant.is_empty() { "main" } else { variant }.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub solution: Solution,
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+    pub input_hash: u64,
+}
+
+/// `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+impl Report {
+    /// A single line JSON object. The answer is a string since it may not
+    /// fit in the numbers JSON readers support, and so is the hash.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"year\":{},\"day\":{},\"part\":{},\"variant\":{},\"answer\":{},\"parse_ns\":{},\"solve_ns\":{},\"input_hash\":\"{:016x}\"}}",
+            self.solution.year,
+            self.solution.day,
+            self.solution.part,
+            json_string(&self.solution.variant),
+            json_string(&self.answer),
+            self.parse.as_nanos(),
+            self.solve.as_nanos(),
+            self.input_hash
+        )
+    }
+}
+
+/// Times a solution and prints its answer as `--format` says.
+#[derive(Debug)]
+pub struct Run {
+    solution: Solution,
+    format: Format,
+    started: Instant,
+    parse: Duration,
+}
+
+impl Run {
+    /// Starts timing the solution built as binary `bin`, usually
+    /// `env!("CARGO_BIN_NAME")`.
+    pub fn start(bin: &str, args: impl Iterator<Item = String>) -> Self {
+        Self {
+            solution: Solution::from_bin_name(bin).expect("not the binary of a solution"),
+            format: Format::from_args(args),
+            started: Instant::now(),
+            parse: Duration::ZERO,
+        }
+    }
+
+    /// The input is parsed, what's left is solving.
+    pub fn parsed(&mut self) {
+        self.parse = self.started.elapsed();
+    }
+
+    pub fn report(self, answer: impl fmt::Display, input_hash: u64) -> Report {
+        Report {
+            solve: self.started.elapsed() - self.parse,
+            solution: self.solution,
+            answer: answer.to_string(),
+            parse: self.parse,
+            input_hash,
+        }
+    }
+
+    /// Prints the answer of a solution of the input with hash `input_hash`.
+    pub fn finish(self, answer: impl fmt::Display, input_hash: u64) {
+        let format = self.format;
+        let report = self.report(answer, input_hash);
+
+        match format {
+            Format::Text => println!("{}", report.answer),
+            Format::Json => println!("{}", report.to_json()),
+        }
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// 64 bit FNV-1a, stable across runs and platforms unlike `DefaultHasher`.
+pub fn fingerprint(input: &str) -> u64 {
+    fnv(FNV_OFFSET, input.as_bytes())
+}
+
+/// Hashes everything read through it, so streamed inputs get the same
+/// [`fingerprint`] as whole ones without keeping them around.
+#[derive(Debug)]
+pub struct Hashing<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R> Hashing<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hash: FNV_OFFSET,
+        }
+    }
+
+    /// The fingerprint of what has been read so far.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl<R: Read> Read for Hashing<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hash = fnv(self.hash, &buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Hashing<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The buffer is still filled, so this doesn't read anything.
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.hash = fnv(self.hash, &buf[..amt.min(buf.len())]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader;
+
+    fn args(a: &[&str]) -> impl Iterator<Item = String> {
+        a.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_format_from_args() {
+        assert_eq!(Format::from_args(args(&["d1p1"])), Format::Text);
+        assert_eq!(
+            Format::from_args(args(&["d1p1", "--format", "json"])),
+            Format::Json
+        );
+        assert_eq!(
+            Format::from_args(args(&["d1p1", "--format=json", "--stdin"])),
+            Format::Json
+        );
+        assert_eq!(
+            Format::from_args(args(&["d1p1", "--format=text"])),
+            Format::Text
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown format \"yaml\"")]
+    fn test_unknown_format() {
+        Format::from_args(args(&["d1p1", "--format=yaml"]));
+    }
+
+    #[test]
+    fn test_solution_from_bin_name() {
+        let solution = |day, part, variant: &str| {
+            Some(Solution {
+                year: 2023,
+                day,
+                part,
+                variant: variant.to_string(),
+            })
+        };
+
+        assert_eq!(Solution::from_bin_name("d1p2"), solution(1, 2, "main"));
+        assert_eq!(Solution::from_bin_name("d1p2a1"), solution(1, 2, "a1"));
+        assert_eq!(Solution::from_bin_name("d10p1"), solution(10, 1, "main"));
+        assert_eq!(Solution::from_bin_name("lint"), None);
+        assert_eq!(Solution::from_bin_name("dxp1"), None);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let report = Report {
+            solution: Solution::from_bin_name("d1p2a1").unwrap(),
+            answer: "28\"1".to_string(),
+            parse: Duration::from_nanos(12),
+            solve: Duration::from_micros(3),
+            input_hash: 0xab,
+        };
+
+        assert_eq!(
+            report.to_json(),
+            r#"{"year":2023,"day":1,"part":2,"variant":"a1","answer":"28\"1","parse_ns":12,"solve_ns":3000,"input_hash":"00000000000000ab"}"#
+        );
+    }
+
+    #[test]
+    fn test_run_times_parsing_apart() {
+        let mut run = Run::start("d5p1", args(&["d5p1"]));
+        std::thread::sleep(Duration::from_millis(2));
+        run.parsed();
+        let report = run.report(35, 1);
+
+        assert!(report.parse >= Duration::from_millis(2));
+        assert!(report.solve < report.parse);
+        assert_eq!(report.answer, "35");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_hashing_matches_fingerprint() {
+        let text = "0 3 6\r\n1 3 6 10\n\nlast";
+        let mut hashing = Hashing::new(io::BufReader::with_capacity(4, text.as_bytes()));
+        let mut lines = 0;
+        reader::for_each_line(&mut hashing, |_| lines += 1).unwrap();
+
+        assert_eq!(lines, 4);
+        assert_eq!(hashing.hash(), fingerprint(text));
+
+        let mut read = String::new();
+        let mut hashing = Hashing::new(text.as_bytes());
+        hashing.read_to_string(&mut read).unwrap();
+        assert_eq!(hashing.hash(), fingerprint(text));
+    }
+}